@@ -0,0 +1,18 @@
+//! Compiles the default units database (`src/units.txt`) into static perfect-hash tables, so
+//! that `DEFAULT_REGISTRY` needs no parsing at runtime.
+
+use arshin_dsl::codegen::Table;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    println!("cargo::rerun-if-changed=src/units.txt");
+
+    let units = fs::read_to_string("src/units.txt").unwrap();
+    let table = Table::from_units(&units)
+        .unwrap_or_else(|e| panic!("Invalid default units database: {}", e));
+
+    let out_file = Path::new(&env::var("OUT_DIR").unwrap()).join("default_units.rs");
+    fs::write(out_file, table.generate("DEFAULT_TABLE")).unwrap();
+}
@@ -0,0 +1,631 @@
+//! Rust source of static unit tables (`arshin::registry::StaticRegistry`).
+//!
+//! [`Table`] holds the units, aliases and prefix sets of a registry as plain data, so that it can
+//! be filled by `arshin::codegen` from a registry or by the build script of `arshin` from the
+//! default units database (see [`Table::from_units`]). Generating source needs the `codegen`
+//! feature.
+
+#[cfg(feature = "codegen")]
+use crate::evaluate::{self, COUNT, Powers, Scope, UnitValue};
+#[cfg(feature = "codegen")]
+use crate::prefixes::{BINARY_PREFIXES, SI_PREFIXES};
+#[cfg(feature = "codegen")]
+use crate::syntax::{DefinitionSyntax, StatementKind, UnitSyntax, parse_file};
+#[cfg(feature = "codegen")]
+use std::collections::HashMap;
+
+/// Contents of a static registry.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Table {
+    pub units: Vec<UnitEntry>,
+    /// Alias, canonical unit name.
+    pub aliases: Vec<(String, String)>,
+    /// Unit name, names of the prefix sets it accepts.
+    pub prefixable: Vec<(String, Vec<String>)>,
+    /// Prefix sets besides `standard` and `binary`.
+    pub prefix_sets: Vec<PrefixSetEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitEntry {
+    pub name: String,
+    pub symbol: Option<String>,
+    /// Exponents of the fundamentals, in the order of `arshin::Dimension::new`.
+    pub dimension: [i32; 10],
+    pub transformation: TransformationEntry,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransformationEntry {
+    Identity,
+    Linear {
+        scale: f64,
+        offset: f64,
+    },
+    Logarithmic {
+        base: f64,
+        factor: f64,
+        reference: f64,
+        /// Gain unit (power ratio), see `arshin::LogarithmicTransformation::new_gain`.
+        gain: bool,
+    },
+}
+
+/// Name of a prefix set, then name, symbol (empty if none) and factor of each prefix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrefixSetEntry {
+    pub name: String,
+    pub prefixes: Vec<(String, String, f64)>,
+}
+
+#[cfg(feature = "codegen")]
+impl Table {
+    /// Generates Rust source of a `pub static` `StaticRegistry` named `name`.
+    ///
+    /// The source refers to the crate as `::arshin`, so it can be `include!`d anywhere depending
+    /// on it. Entries are sorted, so the same table always produces the same source.
+    pub fn generate(&self, name: &str) -> String {
+        let mut units: Vec<&UnitEntry> = self.units.iter().collect();
+        units.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut units_map = phf_codegen::Map::new();
+        units_map.phf_path("::arshin::phf");
+        for unit in units {
+            units_map.entry(unit.name.as_str(), &unit_source(unit));
+        }
+
+        let mut aliases: Vec<&(String, String)> = self.aliases.iter().collect();
+        aliases.sort();
+        let mut aliases_map = phf_codegen::Map::new();
+        aliases_map.phf_path("::arshin::phf");
+        for (alias, unit_name) in aliases {
+            aliases_map.entry(alias.as_str(), &format!("{:?}", unit_name));
+        }
+
+        let mut prefixable: Vec<&(String, Vec<String>)> = self.prefixable.iter().collect();
+        prefixable.sort();
+        let mut prefixable_map = phf_codegen::Map::new();
+        prefixable_map.phf_path("::arshin::phf");
+        for (unit_name, sets) in prefixable {
+            let sets: Vec<String> = sets.iter().map(|set| format!("{:?}", set)).collect();
+            prefixable_map.entry(unit_name.as_str(), &format!("&[{}]", sets.join(", ")));
+        }
+
+        let prefix_sets: Vec<String> = self
+            .prefix_sets
+            .iter()
+            .map(|set| {
+                let prefixes: Vec<String> = set
+                    .prefixes
+                    .iter()
+                    .map(|(name, symbol, factor)| {
+                        format!("({:?}, {:?}, {})", name, symbol, float_source(*factor))
+                    })
+                    .collect();
+                format!("({:?}, &[{}])", set.name, prefixes.join(", "))
+            })
+            .collect();
+
+        format!(
+            "/// Static unit tables generated by `arshin::codegen`.\n\
+             #[allow(clippy::approx_constant)]\n\
+             pub static {}: ::arshin::registry::StaticRegistry = ::arshin::registry::StaticRegistry {{\n\
+             \x20   units: {},\n\
+             \x20   aliases: {},\n\
+             \x20   prefixable: {},\n\
+             \x20   prefix_sets: &[{}],\n\
+             }};\n",
+            name,
+            units_map.build(),
+            aliases_map.build(),
+            prefixable_map.build(),
+            prefix_sets.join(", "),
+        )
+    }
+}
+
+#[cfg(feature = "codegen")]
+impl Table {
+    /// Evaluates the content of a units file without imports (e.g. the default units database).
+    ///
+    /// Definitions are evaluated by [`evaluate`] like in the runtime parser of `arshin`; this
+    /// only records the resulting units, aliases and prefix sets. Registry checks besides clashing
+    /// names, like prefix collisions, are left to the runtime parser, which `arshin` runs on the
+    /// default units database in its tests.
+    ///
+    /// # Errors
+    /// A message naming the offending definition for invalid syntax, imports, invalid
+    /// definitions (see [`evaluate`]) and clashing names.
+    pub fn from_units(content: &str) -> Result<Table, String> {
+        let mut builder = TableBuilder::new();
+
+        for statement in parse_file(content, "<units>").map_err(|e| e.message)? {
+            let (name, result) = match statement.kind {
+                StatementKind::Import(path) => {
+                    return Err(format!("imports are not supported: {}", path));
+                }
+                StatementKind::PrefixSet(set) => (
+                    set.name.clone(),
+                    evaluate::prefix_set(set)
+                        .map_err(|e| e.to_string())
+                        .and_then(|set| builder.register_prefix_set(set)),
+                ),
+                StatementKind::Unit(unit) => (unit.name.clone(), builder.define(unit)),
+            };
+
+            result.map_err(|message| {
+                format!(
+                    "{}:{}: {}: {}",
+                    statement.line, statement.column, name, message
+                )
+            })?;
+        }
+
+        Ok(builder.into_table())
+    }
+}
+
+#[cfg(feature = "codegen")]
+impl UnitValue for UnitEntry {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn powers(&self) -> Powers {
+        self.dimension
+    }
+
+    fn transformation(&self) -> Option<TransformationEntry> {
+        Some(self.transformation)
+    }
+
+    fn mul(self, rhs: Self) -> Self {
+        let dimension = combined(self.dimension, rhs.dimension, 1);
+        UnitEntry::linear(dimension, self.scale() * rhs.scale())
+    }
+
+    fn div(self, rhs: Self) -> Self {
+        let dimension = combined(self.dimension, rhs.dimension, -1);
+        UnitEntry::linear(dimension, self.scale() / rhs.scale())
+    }
+
+    fn pow(self, power: i32) -> Self {
+        let dimension = combined(COUNT, self.dimension, power);
+        match self.transformation {
+            TransformationEntry::Identity => UnitEntry {
+                transformation: TransformationEntry::Identity,
+                ..UnitEntry::linear(dimension, 1.0)
+            },
+            _ => UnitEntry::linear(dimension, self.scale().powf(power as f64)),
+        }
+    }
+}
+
+#[cfg(feature = "codegen")]
+impl UnitEntry {
+    /// Unnamed intermediate unit of an expression.
+    fn linear(dimension: Powers, scale: f64) -> Self {
+        UnitEntry {
+            name: String::new(),
+            symbol: None,
+            dimension,
+            transformation: TransformationEntry::Linear { scale, offset: 0.0 },
+        }
+    }
+
+    fn scale(&self) -> f64 {
+        match self.transformation {
+            TransformationEntry::Linear { scale, .. } => scale,
+            _ => 1.0,
+        }
+    }
+}
+
+/// Powers of `a * b^power`, collapsed to `count` when dimensionless like `Dimension::new`.
+#[cfg(feature = "codegen")]
+fn combined(a: Powers, b: Powers, power: i32) -> Powers {
+    let mut powers = a;
+    powers.iter_mut().zip(b).for_each(|(x, y)| *x += y * power);
+    powers[9] = match powers[..9].iter().all(|power| *power == 0) {
+        true => 1,
+        false => 0,
+    };
+    powers
+}
+
+/// Units, aliases and prefix sets defined so far, with lookups like those of `UnitRegistry`.
+#[cfg(feature = "codegen")]
+struct TableBuilder {
+    units: HashMap<String, UnitEntry>,
+    aliases: HashMap<String, String>,
+    prefixable: HashMap<String, Vec<String>>,
+    prefix_sets: Vec<PrefixSetEntry>,
+}
+
+#[cfg(feature = "codegen")]
+impl Scope for TableBuilder {
+    type Unit = UnitEntry;
+
+    fn unit(&self, name: &str) -> Option<UnitEntry> {
+        self.get_prefixed(name)
+    }
+
+    fn number(&self, text: &str, value: f64) -> UnitEntry {
+        UnitEntry {
+            name: text.into(),
+            ..UnitEntry::linear(COUNT, value)
+        }
+    }
+}
+
+#[cfg(feature = "codegen")]
+impl TableBuilder {
+    fn new() -> Self {
+        let builtin = |name: &str, prefixes: &[(&str, &str, f64)]| PrefixSetEntry {
+            name: name.into(),
+            prefixes: prefixes
+                .iter()
+                .map(|(name, symbol, factor)| (name.to_string(), symbol.to_string(), *factor))
+                .collect(),
+        };
+
+        TableBuilder {
+            units: HashMap::new(),
+            aliases: HashMap::new(),
+            prefixable: HashMap::new(),
+            prefix_sets: vec![
+                builtin("standard", &SI_PREFIXES),
+                builtin("binary", &BINARY_PREFIXES),
+            ],
+        }
+    }
+
+    fn into_table(self) -> Table {
+        Table {
+            units: self.units.into_values().collect(),
+            aliases: self.aliases.into_iter().collect(),
+            prefixable: self.prefixable.into_iter().collect(),
+            prefix_sets: self.prefix_sets.into_iter().skip(2).collect(),
+        }
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.units.contains_key(name) || self.aliases.contains_key(name)
+    }
+
+    fn get(&self, name: &str) -> Option<&UnitEntry> {
+        self.units.get(name).or_else(|| {
+            self.aliases
+                .get(name)
+                .and_then(|canonical| self.units.get(canonical))
+        })
+    }
+
+    /// Registered unit, or a prefixed one built like `UnitRegistry::get_prefixed`.
+    fn get_prefixed(&self, name: &str) -> Option<UnitEntry> {
+        if let Some(unit) = self.get(name) {
+            return Some(unit.clone());
+        }
+
+        self.prefix_sets.iter().find_map(|set| {
+            set.prefixes.iter().find_map(|(prefix, symbol, factor)| {
+                let accepts = |rest: &str| {
+                    let canonical = self.aliases.get(rest).map_or(rest, String::as_str);
+                    self.prefixable
+                        .get(canonical)
+                        .is_some_and(|sets| sets.contains(&set.name))
+                };
+
+                let unit = match (
+                    name.strip_prefix(prefix.as_str()),
+                    name.strip_prefix(symbol.as_str()),
+                ) {
+                    (Some(rest), _)
+                        if self.get(rest).is_some_and(|unit| {
+                            accepts(rest)
+                                && (self.units.contains_key(rest)
+                                    || unit.symbol.as_deref() != Some(rest))
+                        }) =>
+                    {
+                        self.get(rest)
+                    }
+                    (_, Some(rest))
+                        if !symbol.is_empty()
+                            && self.get(rest).is_some_and(|unit| {
+                                accepts(rest) && unit.symbol.as_deref() == Some(rest)
+                            }) =>
+                    {
+                        self.get(rest)
+                    }
+                    _ => None,
+                }?;
+
+                Some(UnitEntry {
+                    name: name.into(),
+                    symbol: match &unit.symbol {
+                        Some(unit_symbol) if !symbol.is_empty() => {
+                            Some(format!("{}{}", symbol, unit_symbol))
+                        }
+                        _ => None,
+                    },
+                    ..UnitEntry::linear(unit.dimension, unit.scale() * factor)
+                })
+            })
+        })
+    }
+
+    fn register_prefix_set(&mut self, set: PrefixSetEntry) -> Result<(), String> {
+        if set.name == "no" || self.prefix_sets.iter().any(|s| s.name == set.name) {
+            return Err(format!("prefix set {} is already defined", set.name));
+        }
+
+        self.prefix_sets.push(set);
+        Ok(())
+    }
+
+    fn register(&mut self, unit: UnitEntry) -> Result<(), String> {
+        if self.contains(&unit.name) {
+            return Err(format!("{} is already defined", unit.name));
+        }
+        if let Some(symbol) = unit.symbol.clone().filter(|symbol| *symbol != unit.name) {
+            self.register_alias(&symbol, &unit.name)?;
+        }
+
+        self.units.insert(unit.name.clone(), unit);
+        Ok(())
+    }
+
+    fn register_alias(&mut self, alias: &str, name: &str) -> Result<(), String> {
+        if self.contains(alias) {
+            return Err(format!("{} is already defined", alias));
+        }
+
+        self.aliases.insert(alias.into(), name.into());
+        Ok(())
+    }
+
+    fn define(&mut self, syntax: UnitSyntax) -> Result<(), String> {
+        let (dimension, transformation) = match &syntax.definition {
+            DefinitionSyntax::Base {
+                dimension,
+                transformation,
+            } => (
+                evaluate::dimension(dimension).map_err(|e| e.to_string())?,
+                evaluate::transformation(transformation).map_err(|e| e.to_string())?,
+            ),
+            DefinitionSyntax::Derived { expression } => {
+                let unit = evaluate::derived_unit(self, expression).map_err(|e| e.to_string())?;
+                (unit.dimension, unit.transformation)
+            }
+        };
+        let unit = UnitEntry {
+            name: syntax.name.clone(),
+            symbol: syntax.symbol.clone(),
+            dimension,
+            transformation,
+        };
+
+        let delta = match unit.transformation {
+            TransformationEntry::Linear { scale, offset } if offset != 0.0 => Some(UnitEntry {
+                name: format!("delta_{}", unit.name),
+                symbol: unit.symbol.as_ref().map(|symbol| format!("Δ{}", symbol)),
+                ..UnitEntry::linear(unit.dimension, scale)
+            }),
+            _ => None,
+        };
+
+        self.register(unit)?;
+        for alias in &syntax.aliases {
+            self.register_alias(alias, &syntax.name)?;
+        }
+
+        if let Some(delta) = delta
+            && !self.contains(&delta.name)
+        {
+            let delta_name = delta.name.clone();
+            self.register(delta)?;
+            for alias in &syntax.aliases {
+                self.register_alias(&format!("delta_{}", alias), &delta_name)?;
+            }
+        }
+
+        for set in syntax.prefixes {
+            if !self.prefix_sets.iter().any(|s| s.name == set) {
+                return Err(format!("unknown prefix set {}", set));
+            }
+            let sets = self.prefixable.entry(syntax.name.clone()).or_default();
+            if !sets.contains(&set) {
+                sets.push(set);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "codegen")]
+fn unit_source(unit: &UnitEntry) -> String {
+    let transformation = match unit.transformation {
+        TransformationEntry::Identity => "::arshin::UnitTransformation::Identity".into(),
+        TransformationEntry::Linear { scale, offset } => format!(
+            "::arshin::UnitTransformation::Linear(::arshin::LinearTransformation::new({}, {}))",
+            float_source(scale),
+            float_source(offset)
+        ),
+        TransformationEntry::Logarithmic {
+            base,
+            factor,
+            reference,
+            gain: false,
+        } => format!(
+            "::arshin::UnitTransformation::Logarithmic(::arshin::LogarithmicTransformation::new({}, {}, {}))",
+            float_source(base),
+            float_source(factor),
+            float_source(reference)
+        ),
+        TransformationEntry::Logarithmic { base, factor, .. } => format!(
+            "::arshin::UnitTransformation::Logarithmic(::arshin::LogarithmicTransformation::new_gain({}, {}))",
+            float_source(base),
+            float_source(factor)
+        ),
+    };
+
+    format!(
+        "::arshin::Unit::new_static({:?}, {:?}, ::arshin::Dimension::new({:?}), {})",
+        unit.name, unit.symbol, unit.dimension, transformation
+    )
+}
+
+#[cfg(feature = "codegen")]
+fn float_source(value: f64) -> String {
+    if value.is_finite() {
+        format!("{:?}f64", value)
+    } else {
+        format!("f64::from_bits({:#x})", value.to_bits())
+    }
+}
+
+#[cfg(all(test, feature = "codegen"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() {
+        let table = Table {
+            units: vec![UnitEntry {
+                name: "arshin".into(),
+                symbol: Some("ar".into()),
+                dimension: [0, 1, 0, 0, 0, 0, 0, 0, 0, 0],
+                transformation: TransformationEntry::Linear {
+                    scale: 0.7112,
+                    offset: 0.0,
+                },
+            }],
+            aliases: vec![("ar".into(), "arshin".into())],
+            prefixable: vec![("arshin".into(), vec!["russian".into()])],
+            prefix_sets: vec![PrefixSetEntry {
+                name: "russian".into(),
+                prefixes: vec![("pol".into(), String::new(), 0.5)],
+            }],
+        };
+
+        let source = table.generate("RUSSIAN_UNITS");
+        assert!(source.starts_with("/// Static unit tables"));
+        assert!(source.contains("pub static RUSSIAN_UNITS: ::arshin::registry::StaticRegistry"));
+        assert!(source.contains(
+            "::arshin::Unit::new_static(\"arshin\", Some(\"ar\"), \
+             ::arshin::Dimension::new([0, 1, 0, 0, 0, 0, 0, 0, 0, 0]), \
+             ::arshin::UnitTransformation::Linear(::arshin::LinearTransformation::new(0.7112f64, 0.0f64)))"
+        ));
+        assert!(source.contains("prefix_sets: &[(\"russian\", &[(\"pol\", \"\", 0.5f64)])]"));
+        assert_eq!(source, table.generate("RUSSIAN_UNITS"));
+    }
+
+    #[test]
+    fn test_float_source() {
+        assert_eq!(float_source(1e-30), "1e-30f64");
+        assert_eq!(float_source(273.15), "273.15f64");
+        assert_eq!(
+            float_source(f64::INFINITY),
+            "f64::from_bits(0x7ff0000000000000)"
+        );
+    }
+
+    fn unit<'a>(table: &'a Table, name: &str) -> &'a UnitEntry {
+        table.units.iter().find(|unit| unit.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_from_units() {
+        let table = Table::from_units(
+            r#"
+            prefix_set russian { pol = 0.5 }
+            unit meter {
+                dimension: length
+                transformation: identity
+                prefixes: standard, russian
+                symbol: m
+            }
+            unit second {
+                dimension: time
+                transformation: identity
+                prefixes: standard
+                symbol: s
+            }
+            unit degree_celsius {
+                dimension: temperature
+                transformation: linear(scale: 1, offset: 273.15)
+                prefixes: no
+                symbol: °C
+                aliases: [celsius]
+            }
+            unit inch = 2.54 centimeter
+            unit kilometer_per_hour = km / (60 * 60 s)
+            unit hertz = 1 / second
+            unit arshin = 1.4224 polmeter
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            unit(&table, "meter").transformation,
+            TransformationEntry::Identity
+        );
+        assert_eq!(
+            unit(&table, "inch").transformation,
+            TransformationEntry::Linear {
+                scale: 2.54 * 1e-2,
+                offset: 0.0
+            }
+        );
+        assert_eq!(
+            unit(&table, "kilometer_per_hour").transformation,
+            TransformationEntry::Linear {
+                scale: 1e3 / (60.0 * 60.0),
+                offset: 0.0
+            }
+        );
+        assert_eq!(
+            unit(&table, "hertz").dimension,
+            [0, 0, -1, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            unit(&table, "arshin").transformation,
+            TransformationEntry::Linear {
+                scale: 1.4224 * 0.5,
+                offset: 0.0
+            }
+        );
+
+        let delta = unit(&table, "delta_degree_celsius");
+        assert_eq!(delta.symbol.as_deref(), Some("Δ°C"));
+        assert!(
+            table
+                .aliases
+                .contains(&("delta_celsius".into(), "delta_degree_celsius".into()))
+        );
+        assert_eq!(table.prefix_sets.len(), 1);
+
+        assert!(Table::from_units("unit inch = 2.54 centimeter").is_err());
+        assert!(Table::from_units("import \"units.txt\"").is_err());
+    }
+
+    #[test]
+    fn test_from_units_invalid() {
+        let invalid = [
+            "unit x { dimension: length transformation: linear(scale: 0) prefixes: no }",
+            "unit x { dimension: count transformation: decibel(p0: -1) prefixes: no }",
+            "unit x { dimension: count transformation: log(base: 1) prefixes: no }",
+            "unit x { dimension: length^99999999999 transformation: identity prefixes: no }",
+            "unit x { dimension: length^2147483647 * length transformation: identity prefixes: no }",
+            "prefix_set x { half = 0.5 nothing = 0 }",
+            "prefix_set x { kilo(k) = 1e3 kilo(K) = 1e3 }",
+            "unit m { dimension: length transformation: identity prefixes: no }
+             unit x = m^99999999999",
+            "unit m { dimension: length transformation: identity prefixes: no }
+             unit x = m^2147483647 * m",
+        ];
+        for content in invalid {
+            assert!(Table::from_units(content).is_err(), "{}", content);
+        }
+    }
+}
@@ -0,0 +1,426 @@
+//! Evaluation of unit definitions and unit expressions.
+//!
+//! This is the single evaluator of the units file language: the runtime parser of `arshin`
+//! (`arshin::parser`) evaluates definitions and expressions against a registry with it, and
+//! `Table::from_units` (`codegen` feature) against the units defined so far, so both accept the
+//! same definitions and compute the same units.
+
+use crate::codegen::{PrefixSetEntry, TransformationEntry};
+use crate::syntax::{
+    DimensionTerm, Expression, Factor, Operator, PrefixSetSyntax, Primary, Suffix,
+    TransformationSyntax, parse_expression,
+};
+use std::fmt;
+
+/// Exponents of the fundamentals, in the order of `arshin::Dimension::new`.
+pub type Powers = [i32; 10];
+
+/// Fundamentals in the order of `arshin::Dimension::new`.
+pub const FUNDAMENTALS: [&str; 10] = [
+    "mass",
+    "length",
+    "time",
+    "current",
+    "temperature",
+    "amount of substance",
+    "luminosity",
+    "angle",
+    "bit",
+    "count",
+];
+
+/// Powers of dimensionless units.
+pub const COUNT: Powers = [0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvaluationError {
+    /// Invalid number, exponent or transformation parameter of a definition.
+    InvalidDefinition(String),
+    /// Reference to a unit that is not defined.
+    UndefinedUnit(String),
+    /// Expression that cannot be evaluated, e.g. a biased unit combined with other units.
+    InvalidExpression { expression: String, message: String },
+    /// Prefix set defining a prefix name or symbol twice.
+    PrefixCollision { set: String, prefix: String },
+    /// Invalid syntax of an expression.
+    Syntax(String),
+}
+
+impl fmt::Display for EvaluationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvaluationError::InvalidDefinition(message) | EvaluationError::Syntax(message) => {
+                write!(f, "{}", message)
+            }
+            EvaluationError::UndefinedUnit(name) => write!(f, "undefined unit {}", name),
+            EvaluationError::InvalidExpression {
+                expression,
+                message,
+            } => write!(f, "invalid unit expression {}: {}", expression, message),
+            EvaluationError::PrefixCollision { set, prefix } => {
+                write!(f, "prefix {} is defined twice in {}", prefix, set)
+            }
+        }
+    }
+}
+
+/// Unit as seen by the evaluator.
+///
+/// The evaluator checks operands before combining them, so `mul`, `div` and `pow` are only
+/// called on multiplicative units and never overflow the powers of the dimension.
+pub trait UnitValue: Sized {
+    fn name(&self) -> &str;
+
+    fn powers(&self) -> Powers;
+
+    /// Transformation of the unit, `None` for custom transformations.
+    fn transformation(&self) -> Option<TransformationEntry>;
+
+    fn mul(self, rhs: Self) -> Self;
+
+    fn div(self, rhs: Self) -> Self;
+
+    fn pow(self, power: i32) -> Self;
+}
+
+/// Units that names in expressions refer to.
+pub trait Scope {
+    type Unit: UnitValue;
+
+    /// Unit named `name`, with or without a prefix.
+    fn unit(&self, name: &str) -> Option<Self::Unit>;
+
+    /// Dimensionless unit of the number `value`, written as `text`.
+    fn number(&self, text: &str, value: f64) -> Self::Unit;
+}
+
+/// Parses and evaluates a unit expression, e.g. `kilogram * meter / second^2`.
+///
+/// # Errors
+/// Invalid syntax, undefined units, invalid numbers or exponents, and biased, logarithmic or
+/// custom units combined with other units.
+pub fn unit_expression<S: Scope>(scope: &S, expression: &str) -> Result<S::Unit, EvaluationError> {
+    let expression =
+        parse_expression(expression).map_err(|e| EvaluationError::Syntax(e.message))?;
+
+    evaluate_expression(scope, &expression)
+}
+
+/// Evaluates the expression of a derived unit definition like `unit inch = 2.54 centimeter`.
+///
+/// # Errors
+/// Expression errors (see [`unit_expression`]) and a zero or infinite scale.
+pub fn derived_unit<S: Scope>(scope: &S, expression: &str) -> Result<S::Unit, EvaluationError> {
+    let unit = unit_expression(scope, expression)?;
+    if let Some(TransformationEntry::Linear { scale, .. }) = unit.transformation() {
+        check_scale(scale)?;
+    }
+
+    Ok(unit)
+}
+
+/// Powers of the `dimension:` property of a base unit, e.g. `mass * length / time^2`.
+///
+/// # Errors
+/// Unknown fundamentals, and zero exponents or exponents out of the range of [`Powers`].
+pub fn dimension(terms: &[DimensionTerm]) -> Result<Powers, EvaluationError> {
+    let mut result = COUNT;
+    for term in terms {
+        let index = FUNDAMENTALS
+            .iter()
+            .position(|fundamental| *fundamental == term.fundamental)
+            .ok_or_else(|| {
+                EvaluationError::InvalidDefinition(format!(
+                    "unknown fundamental {}",
+                    term.fundamental
+                ))
+            })?;
+        let invalid_exponent = |exponent: &str| {
+            EvaluationError::InvalidDefinition(format!("invalid exponent {}", exponent))
+        };
+        let exponent = match &term.exponent {
+            Some(exponent) => exponent
+                .parse::<i32>()
+                .ok()
+                .filter(|exponent| *exponent != 0)
+                .ok_or_else(|| invalid_exponent(exponent))?,
+            None => 1,
+        };
+
+        let mut fundamental = [0; 10];
+        fundamental[index] = exponent;
+        let sign = if term.inverse { -1 } else { 1 };
+        result = combined_powers(result, fundamental, sign)
+            .ok_or_else(|| invalid_exponent(&exponent.to_string()))?;
+    }
+
+    Ok(result)
+}
+
+/// Transformation of the `transformation:` property of a base unit.
+///
+/// # Errors
+/// Invalid numbers, a zero or infinite scale, and an invalid logarithm base, factor or reference
+/// (e.g. a non-positive decibel `p0`).
+pub fn transformation(
+    syntax: &TransformationSyntax,
+) -> Result<TransformationEntry, EvaluationError> {
+    let invalid = |message: String| Err(EvaluationError::InvalidDefinition(message));
+
+    match syntax {
+        TransformationSyntax::Identity => Ok(TransformationEntry::Identity),
+        TransformationSyntax::Linear { scale, offset } => {
+            let scale = number(scale)?;
+            check_scale(scale)?;
+            let offset = offset.as_deref().map_or(Ok(0.0), number)?;
+            Ok(TransformationEntry::Linear { scale, offset })
+        }
+        TransformationSyntax::Decibel { p0, factor, gain } => {
+            let p0 = number(p0)?;
+            if p0 <= 0.0 || !p0.is_finite() {
+                return invalid(format!("p0 must be a finite positive number, got {}", p0));
+            }
+            let factor = factor.as_deref().map_or(Ok(10.0), number)?;
+            if factor <= 0.0 || !factor.is_finite() {
+                return invalid(format!(
+                    "factor must be a finite positive number, got {}",
+                    factor
+                ));
+            }
+            check_gain(*gain, p0)?;
+            Ok(TransformationEntry::Logarithmic {
+                base: 10.0,
+                factor,
+                reference: p0,
+                gain: *gain,
+            })
+        }
+        TransformationSyntax::Logarithmic {
+            base,
+            factor,
+            reference,
+            gain,
+        } => {
+            let base = base.as_deref().map_or(Ok(std::f64::consts::E), number)?;
+            let factor = factor.as_deref().map_or(Ok(1.0), number)?;
+            let reference = reference.as_deref().map_or(Ok(1.0), number)?;
+
+            if base <= 0.0 || base == 1.0 || !base.is_finite() {
+                return invalid(format!(
+                    "log base must be a finite positive number other than 1, got {}",
+                    base
+                ));
+            }
+            if factor == 0.0 || !factor.is_finite() {
+                return invalid(format!(
+                    "factor must be a finite non-zero number, got {}",
+                    factor
+                ));
+            }
+            if reference <= 0.0 || !reference.is_finite() {
+                return invalid(format!(
+                    "ref must be a finite positive number, got {}",
+                    reference
+                ));
+            }
+            check_gain(*gain, reference)?;
+            Ok(TransformationEntry::Logarithmic {
+                base,
+                factor,
+                reference,
+                gain: *gain,
+            })
+        }
+    }
+}
+
+/// Evaluates a `prefix_set` declaration.
+///
+/// # Errors
+/// Invalid or non-positive factors, and prefix names or symbols defined twice.
+pub fn prefix_set(syntax: PrefixSetSyntax) -> Result<PrefixSetEntry, EvaluationError> {
+    let mut prefixes: Vec<(String, String, f64)> = Vec::new();
+    for prefix in syntax.prefixes {
+        let symbol = prefix.symbol.unwrap_or_default();
+        let factor = number(&prefix.factor)?;
+
+        if factor <= 0.0 || !factor.is_finite() {
+            return Err(EvaluationError::InvalidDefinition(format!(
+                "factor of prefix {} must be a finite positive number, got {}",
+                prefix.name, factor
+            )));
+        }
+        if let Some((existing, _, _)) = prefixes.iter().find(|(name, existing, _)| {
+            *name == prefix.name || (!symbol.is_empty() && *existing == symbol)
+        }) {
+            return Err(EvaluationError::PrefixCollision {
+                set: syntax.name,
+                prefix: if *existing == prefix.name {
+                    prefix.name
+                } else {
+                    symbol
+                },
+            });
+        }
+        prefixes.push((prefix.name, symbol, factor));
+    }
+
+    Ok(PrefixSetEntry {
+        name: syntax.name,
+        prefixes,
+    })
+}
+
+/// Checks the scale of a linear unit.
+///
+/// # Errors
+/// `InvalidDefinition` for a zero, infinite or NaN scale.
+pub fn check_scale(scale: f64) -> Result<(), EvaluationError> {
+    if scale == 0.0 || !scale.is_finite() {
+        return Err(EvaluationError::InvalidDefinition(format!(
+            "scale must be a finite non-zero number, got {}",
+            scale
+        )));
+    }
+
+    Ok(())
+}
+
+/// Gains are ratios of two powers, so their reference is 1.
+fn check_gain(gain: bool, reference: f64) -> Result<(), EvaluationError> {
+    if gain && reference != 1.0 {
+        return Err(EvaluationError::InvalidDefinition(format!(
+            "gain units must have a reference of 1, got {}",
+            reference
+        )));
+    }
+
+    Ok(())
+}
+
+fn number(text: &str) -> Result<f64, EvaluationError> {
+    text.parse::<f64>()
+        .map_err(|e| EvaluationError::InvalidDefinition(format!("invalid number {}: {}", text, e)))
+}
+
+fn evaluate_expression<S: Scope>(
+    scope: &S,
+    expression: &Expression,
+) -> Result<S::Unit, EvaluationError> {
+    let mut result = evaluate_factor(scope, &expression.first)?;
+
+    for (operator, factor) in expression.rest.iter() {
+        let rhs = evaluate_factor(scope, factor)?;
+        check_multiplicative(&result, &expression.text)?;
+        check_multiplicative(&rhs, &expression.text)?;
+        result = match operator {
+            Operator::Mul => {
+                check_powers(
+                    combined_powers(result.powers(), rhs.powers(), 1),
+                    expression,
+                )?;
+                result.mul(rhs)
+            }
+            Operator::Div => {
+                check_powers(
+                    combined_powers(result.powers(), rhs.powers(), -1),
+                    expression,
+                )?;
+                result.div(rhs)
+            }
+        };
+    }
+
+    Ok(result)
+}
+
+fn evaluate_factor<S: Scope>(scope: &S, factor: &Factor) -> Result<S::Unit, EvaluationError> {
+    let invalid = |message: String| EvaluationError::InvalidExpression {
+        expression: factor.text.clone(),
+        message,
+    };
+
+    let unit = match &factor.primary {
+        Primary::Number(text) => {
+            let value = text.parse::<f64>().map_err(|e| invalid(e.to_string()))?;
+            scope.number(text, value)
+        }
+        Primary::Name(name) => scope
+            .unit(name)
+            .ok_or_else(|| EvaluationError::UndefinedUnit(name.clone()))?,
+        Primary::Group(group) => evaluate_expression(scope, group)?,
+    };
+
+    match &factor.suffix {
+        Some(Suffix::Factor(rhs)) => {
+            let rhs = evaluate_factor(scope, rhs)?;
+            check_multiplicative(&unit, &factor.text)?;
+            check_multiplicative(&rhs, &factor.text)?;
+            combined_powers(unit.powers(), rhs.powers(), 1)
+                .ok_or_else(|| invalid("dimension exponent out of range".into()))?;
+            Ok(unit.mul(rhs))
+        }
+        Some(Suffix::Exponent(exponent)) => {
+            let power = exponent
+                .parse::<i32>()
+                .map_err(|e| invalid(e.to_string()))?;
+            if power == 0 {
+                return Err(invalid("zero exponent is not allowed".into()));
+            }
+            check_multiplicative(&unit, &factor.text)?;
+            unit.powers()
+                .iter()
+                .try_for_each(|x| x.checked_mul(power).map(drop))
+                .ok_or_else(|| invalid("dimension exponent out of range".into()))?;
+            Ok(unit.pow(power))
+        }
+        None => Ok(unit),
+    }
+}
+
+fn check_multiplicative(unit: &impl UnitValue, expression: &str) -> Result<(), EvaluationError> {
+    let message = match unit.transformation() {
+        Some(TransformationEntry::Logarithmic { .. }) => format!(
+            "logarithmic unit {} cannot be combined with other units",
+            unit.name()
+        ),
+        Some(TransformationEntry::Linear { offset, .. }) if offset != 0.0 => format!(
+            "biased unit {} cannot be combined with other units",
+            unit.name()
+        ),
+        None => format!(
+            "unit {} with custom transformation cannot be combined with other units",
+            unit.name()
+        ),
+        _ => return Ok(()),
+    };
+
+    Err(EvaluationError::InvalidExpression {
+        expression: expression.into(),
+        message,
+    })
+}
+
+fn check_powers(powers: Option<Powers>, expression: &Expression) -> Result<(), EvaluationError> {
+    powers
+        .map(drop)
+        .ok_or_else(|| EvaluationError::InvalidExpression {
+            expression: expression.text.clone(),
+            message: "dimension exponent out of range".into(),
+        })
+}
+
+/// Powers of `a * b^sign`, collapsed to [`COUNT`] when dimensionless like `Dimension::new`;
+/// `None` on overflow.
+fn combined_powers(a: Powers, b: Powers, sign: i32) -> Option<Powers> {
+    let mut powers = a;
+    for (x, y) in powers.iter_mut().zip(b) {
+        *x = x.checked_add(y.checked_mul(sign)?)?;
+    }
+    powers[9] = match powers[..9].iter().all(|power| *power == 0) {
+        true => 1,
+        false => 0,
+    };
+
+    Some(powers)
+}
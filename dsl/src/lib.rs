@@ -0,0 +1,11 @@
+//! Units file language of `arshin`: its grammar and syntax tree, and the evaluation of
+//! definitions shared by the library's runtime parser and by its build script, which compiles
+//! the default units database into static tables (`codegen` feature).
+//!
+//! This is an internal crate of `arshin` and its API may change in any release; use the units
+//! file API of `arshin` itself (`arshin::parser`, `arshin::codegen`).
+
+pub mod codegen;
+pub mod evaluate;
+pub mod prefixes;
+pub mod syntax;
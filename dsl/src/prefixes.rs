@@ -0,0 +1,39 @@
+/// SI (decimal) prefixes: name, symbol, factor.
+pub const SI_PREFIXES: [(&str, &str, f64); 24] = [
+    ("Quetta", "Q", 1e30),
+    ("Ronna", "R", 1e27),
+    ("Yotta", "Y", 1e24),
+    ("Zetta", "Z", 1e21),
+    ("Exa", "E", 1e18),
+    ("Peta", "P", 1e15),
+    ("Tera", "T", 1e12),
+    ("Giga", "G", 1e9),
+    ("Mega", "M", 1e6),
+    ("kilo", "k", 1e3),
+    ("hecto", "h", 1e2),
+    ("deca", "da", 1e1),
+    ("deci", "d", 1e-1),
+    ("centi", "c", 1e-2),
+    ("milli", "m", 1e-3),
+    ("micro", "µ", 1e-6),
+    ("nano", "n", 1e-9),
+    ("pico", "p", 1e-12),
+    ("femto", "f", 1e-15),
+    ("atto", "a", 1e-18),
+    ("zepto", "z", 1e-21),
+    ("yocto", "y", 1e-24),
+    ("ronto", "r", 1e-27),
+    ("quecto", "q", 1e-30),
+];
+
+/// IEC (binary) prefixes: name, symbol, factor.
+pub const BINARY_PREFIXES: [(&str, &str, f64); 8] = [
+    ("kibi", "Ki", 1024.0),
+    ("mebi", "Mi", 1048576.0),
+    ("gibi", "Gi", 1073741824.0),
+    ("tebi", "Ti", 1099511627776.0),
+    ("pebi", "Pi", 1125899906842624.0),
+    ("exbi", "Ei", 1152921504606846976.0),
+    ("zebi", "Zi", 1180591620717411303424.0),
+    ("yobi", "Yi", 1208925819614629174706176.0),
+];
@@ -0,0 +1,638 @@
+use pest::Parser;
+use pest::iterators::Pair;
+use pest_derive::Parser;
+
+#[derive(Parser)]
+#[grammar = "units.pest"]
+struct UnitsParser;
+
+/// Invalid syntax, with the message of the grammar parser (position, expected rules).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxError {
+    pub message: String,
+}
+
+/// Statement of a units file and its position (1-based line and column).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+    pub line: usize,
+    pub column: usize,
+    pub kind: StatementKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatementKind {
+    /// `import "path"` or `include "path"`.
+    Import(String),
+    PrefixSet(PrefixSetSyntax),
+    Unit(UnitSyntax),
+}
+
+/// `prefix_set name { kilo(k) = 1e3 ... }`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrefixSetSyntax {
+    pub name: String,
+    pub prefixes: Vec<PrefixSyntax>,
+}
+
+/// `kilo(k) = 1e3`; the factor is kept as written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrefixSyntax {
+    pub name: String,
+    pub symbol: Option<String>,
+    pub factor: String,
+}
+
+/// `unit name { ... }` or `unit name = expression { ... }`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitSyntax {
+    pub name: String,
+    pub definition: DefinitionSyntax,
+    /// Prefix sets of the `prefixes:` property, empty for `prefixes: no`.
+    pub prefixes: Vec<String>,
+    pub symbol: Option<String>,
+    pub aliases: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DefinitionSyntax {
+    /// `dimension:` and `transformation:` properties.
+    Base {
+        dimension: Vec<DimensionTerm>,
+        transformation: TransformationSyntax,
+    },
+    /// Unit expression over previously defined units, e.g. `2.54 centimeter`.
+    Derived { expression: String },
+}
+
+/// `length`, `time^2` or `/ time^2` of a dimension expression; the exponent is kept as written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DimensionTerm {
+    pub fundamental: String,
+    pub exponent: Option<String>,
+    /// The term follows a `/`.
+    pub inverse: bool,
+}
+
+/// Transformation property; numbers are kept as written.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransformationSyntax {
+    Identity,
+    Linear {
+        scale: String,
+        offset: Option<String>,
+    },
+    Decibel {
+        p0: String,
+        factor: Option<String>,
+        /// `gain: true`: the unit measures gains (power ratios) rather than levels or other ratios.
+        gain: bool,
+    },
+    Logarithmic {
+        /// `None` for `base: e`.
+        base: Option<String>,
+        factor: Option<String>,
+        reference: Option<String>,
+        gain: bool,
+    },
+}
+
+/// Unit expression: factors joined by `*` and `/`, e.g. `kilogram * meter / second^2`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    /// Source text of the expression, trimmed.
+    pub text: String,
+    pub first: Factor,
+    pub rest: Vec<(Operator, Factor)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Mul,
+    Div,
+}
+
+/// Factor of a unit expression: a number, unit name or parenthesized expression, raised to an
+/// exponent (`second^2`) or scaling the next factor (`2.54 centimeter`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Factor {
+    /// Source text of the factor, trimmed.
+    pub text: String,
+    pub primary: Primary,
+    pub suffix: Option<Suffix>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Primary {
+    Number(String),
+    Name(String),
+    Group(Box<Expression>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Suffix {
+    /// Factor scaled by a number, e.g. `centimeter` in `2.54 centimeter`.
+    Factor(Box<Factor>),
+    /// Exponent as written, e.g. `-2`.
+    Exponent(String),
+}
+
+/// Parses the statements of a units file.
+///
+/// # Parameters
+/// - `content`: Content of the file.
+/// - `source`: File name used in error messages.
+///
+/// # Errors
+/// `SyntaxError` if the content does not match the grammar.
+pub fn parse_file(content: &str, source: &str) -> Result<Vec<Statement>, SyntaxError> {
+    let pairs = UnitsParser::parse(Rule::units_list, content).map_err(|e| SyntaxError {
+        message: e.with_path(source).to_string(),
+    })?;
+
+    Ok(pairs
+        .filter_map(|pair| {
+            let (line, column) = pair.line_col();
+            let kind = match pair.as_rule() {
+                Rule::import_statement => StatementKind::Import(
+                    pair.into_inner()
+                        .next()
+                        .map(|path| path.as_str().to_string())
+                        .unwrap_or_default(),
+                ),
+                Rule::prefix_set_definition => StatementKind::PrefixSet(prefix_set(pair)),
+                Rule::unit_definition => StatementKind::Unit(unit(pair)),
+                _ => return None,
+            };
+            Some(Statement { line, column, kind })
+        })
+        .collect())
+}
+
+/// Parses a unit expression such as `kilogram * meter / second^2` or `(kilometer / hour)^-1`.
+///
+/// # Errors
+/// `SyntaxError` if the input is not a unit expression.
+pub fn parse_expression(input: &str) -> Result<Expression, SyntaxError> {
+    let mut pairs =
+        UnitsParser::parse(Rule::unit_expression_input, input).map_err(|e| SyntaxError {
+            message: e.to_string(),
+        })?;
+
+    match pairs.next() {
+        Some(pair) => Ok(expression(pair)),
+        None => unreachable!(),
+    }
+}
+
+/// Splits the leading magnitude (`5`, `-1.5e3`, `.5`, `inf`, `NaN`) off a quantity such as
+/// `5 km`, returning the magnitude as written and the rest of the input.
+///
+/// `nan`, `inf` and `infinity` followed by a letter start a unit name (`nanometer`, `inch`)
+/// rather than a magnitude.
+///
+/// # Returns
+/// `None` if the input does not start with a magnitude.
+pub fn split_magnitude(input: &str) -> Option<(&str, &str)> {
+    let mut pairs = UnitsParser::parse(Rule::magnitude_prefix, input).ok()?;
+    let end = pairs.next()?.as_span().end();
+    Some(input.split_at(end))
+}
+
+fn prefix_set(pair: Pair<Rule>) -> PrefixSetSyntax {
+    let mut inner = pair.into_inner();
+    let name = match inner.next() {
+        Some(name) => name.as_str().to_string(),
+        None => unreachable!(),
+    };
+
+    let prefixes = inner
+        .map(|prefix_pair| {
+            let mut prefix = PrefixSyntax {
+                name: String::new(),
+                symbol: None,
+                factor: String::new(),
+            };
+            for lp in prefix_pair.into_inner() {
+                match lp.as_rule() {
+                    Rule::prefix_name => prefix.name = lp.as_str().to_string(),
+                    Rule::prefix_symbol => prefix.symbol = Some(lp.as_str().to_string()),
+                    Rule::number => prefix.factor = lp.as_str().to_string(),
+                    _ => unreachable!(),
+                }
+            }
+            prefix
+        })
+        .collect();
+
+    PrefixSetSyntax { name, prefixes }
+}
+
+fn unit(pair: Pair<Rule>) -> UnitSyntax {
+    let mut name = String::new();
+    let mut dimension = Vec::new();
+    let mut transformation = TransformationSyntax::Identity;
+    let mut expression = None;
+    let mut prefixes = Vec::new();
+    let mut symbol = None;
+    let mut aliases = Vec::new();
+
+    for inner_pair in pair.into_inner() {
+        match inner_pair.as_rule() {
+            Rule::identifier => name = inner_pair.as_str().into(),
+            Rule::dimension_property => dimension = dimension_property(inner_pair),
+            Rule::transformation_property => transformation = transformation_property(inner_pair),
+            Rule::unit_expression => expression = Some(inner_pair.as_str().trim().to_string()),
+            Rule::prefixes_property => prefixes = prefixes_property(inner_pair),
+            Rule::symbol_property => {
+                symbol = inner_pair
+                    .into_inner()
+                    .next()
+                    .map(|s| s.as_str().to_string())
+            }
+            Rule::aliases_property => {
+                aliases = inner_pair
+                    .into_inner()
+                    .map(|s| s.as_str().to_string())
+                    .collect()
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    let definition = match expression {
+        Some(expression) => DefinitionSyntax::Derived { expression },
+        None => DefinitionSyntax::Base {
+            dimension,
+            transformation,
+        },
+    };
+
+    UnitSyntax {
+        name,
+        definition,
+        prefixes,
+        symbol,
+        aliases,
+    }
+}
+
+fn dimension_property(pair: Pair<Rule>) -> Vec<DimensionTerm> {
+    let mut terms = Vec::new();
+    let mut inverse = false;
+
+    for inner_pair in pair
+        .into_inner()
+        .flat_map(|expression| expression.into_inner())
+    {
+        match inner_pair.as_rule() {
+            Rule::dimension_term => {
+                let mut term = DimensionTerm {
+                    fundamental: String::new(),
+                    exponent: None,
+                    inverse,
+                };
+                for lp in inner_pair.into_inner() {
+                    match lp.as_rule() {
+                        Rule::fundamental => term.fundamental = lp.as_str().to_string(),
+                        Rule::exponent => term.exponent = Some(lp.as_str().to_string()),
+                        _ => {}
+                    }
+                }
+                terms.push(term);
+            }
+            Rule::operator => inverse = inner_pair.as_str() == "/",
+            _ => unreachable!(),
+        }
+    }
+
+    terms
+}
+
+fn transformation_property(pair: Pair<Rule>) -> TransformationSyntax {
+    let Some(inner_pair) = pair
+        .into_inner()
+        .next()
+        .and_then(|transformation| transformation.into_inner().next())
+    else {
+        return TransformationSyntax::Identity;
+    };
+
+    match inner_pair.as_rule() {
+        Rule::identity_transformation => TransformationSyntax::Identity,
+        Rule::linear_transformation => {
+            let mut numbers = inner_pair.into_inner().map(|n| n.as_str().to_string());
+            TransformationSyntax::Linear {
+                scale: numbers.next().unwrap_or_default(),
+                offset: numbers.next(),
+            }
+        }
+        Rule::decibel_transformation => {
+            let (mut numbers, mut gain) = (Vec::new(), false);
+            for parameter in inner_pair.into_inner() {
+                match parameter.as_rule() {
+                    Rule::gain => gain = parameter.into_inner().as_str() == "true",
+                    _ => numbers.push(parameter.as_str().to_string()),
+                }
+            }
+            let mut numbers = numbers.into_iter();
+            TransformationSyntax::Decibel {
+                p0: numbers.next().unwrap_or_default(),
+                factor: numbers.next(),
+                gain,
+            }
+        }
+        Rule::log_transformation => {
+            let (mut base, mut factor, mut reference, mut gain) = (None, None, None, false);
+            for parameter in inner_pair.into_inner() {
+                let rule = parameter.as_rule();
+                let Some(value) = parameter.into_inner().next() else {
+                    unreachable!()
+                };
+                let number = match value.as_rule() {
+                    Rule::euler => None,
+                    _ => Some(value.as_str().to_string()),
+                };
+                match rule {
+                    Rule::log_base => base = number,
+                    Rule::log_factor => factor = number,
+                    Rule::log_reference => reference = number,
+                    Rule::gain => gain = number.as_deref() == Some("true"),
+                    _ => unreachable!(),
+                }
+            }
+            TransformationSyntax::Logarithmic {
+                base,
+                factor,
+                reference,
+                gain,
+            }
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn prefixes_property(pair: Pair<Rule>) -> Vec<String> {
+    pair.into_inner()
+        .flat_map(|expression| expression.into_inner())
+        .map(|set| set.as_str().to_string())
+        .filter(|set| set != "no")
+        .collect()
+}
+
+fn expression(pair: Pair<Rule>) -> Expression {
+    let text = pair.as_str().trim().to_string();
+    let mut inner = pair.into_inner();
+    let first = match inner.next() {
+        Some(first) => factor(first),
+        None => unreachable!(),
+    };
+
+    let mut rest = Vec::new();
+    while let (Some(operator), Some(next)) = (inner.next(), inner.next()) {
+        let operator = match operator.as_str() {
+            "*" => Operator::Mul,
+            "/" => Operator::Div,
+            _ => unreachable!(),
+        };
+        rest.push((operator, factor(next)));
+    }
+
+    Expression { text, first, rest }
+}
+
+fn factor(pair: Pair<Rule>) -> Factor {
+    let text = pair.as_str().trim().to_string();
+    let mut inner = pair.into_inner();
+    let primary = match inner.next() {
+        Some(primary) => match primary.as_rule() {
+            Rule::number => Primary::Number(primary.as_str().to_string()),
+            Rule::unit_name => Primary::Name(primary.as_str().to_string()),
+            Rule::unit_expression => Primary::Group(Box::new(expression(primary))),
+            _ => unreachable!(),
+        },
+        None => unreachable!(),
+    };
+
+    let suffix = inner.next().map(|suffix| match suffix.as_rule() {
+        Rule::unit_factor => Suffix::Factor(Box::new(factor(suffix))),
+        _ => Suffix::Exponent(suffix.as_str().to_string()),
+    });
+
+    Factor {
+        text,
+        primary,
+        suffix,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_file() {
+        let statements = parse_file(
+            r#"
+            import "base.txt"
+            prefix_set russian { pol = 0.5 chetvert(ch) = 0.25 }
+            unit degree_celsius {
+                dimension: temperature
+                transformation: linear(scale: 1, offset: 273.15)
+                prefixes: no
+                symbol: °C
+                aliases: [celsius]
+            }
+            unit arshin = 0.7112 meter { prefixes: russian }
+            "#,
+            "units.txt",
+        )
+        .unwrap();
+
+        assert_eq!(statements.len(), 4);
+        assert_eq!(statements[0].kind, StatementKind::Import("base.txt".into()));
+        assert_eq!((statements[1].line, statements[1].column), (3, 13));
+        assert_eq!(
+            statements[1].kind,
+            StatementKind::PrefixSet(PrefixSetSyntax {
+                name: "russian".into(),
+                prefixes: vec![
+                    PrefixSyntax {
+                        name: "pol".into(),
+                        symbol: None,
+                        factor: "0.5".into()
+                    },
+                    PrefixSyntax {
+                        name: "chetvert".into(),
+                        symbol: Some("ch".into()),
+                        factor: "0.25".into()
+                    },
+                ],
+            })
+        );
+        assert_eq!(
+            statements[2].kind,
+            StatementKind::Unit(UnitSyntax {
+                name: "degree_celsius".into(),
+                definition: DefinitionSyntax::Base {
+                    dimension: vec![DimensionTerm {
+                        fundamental: "temperature".into(),
+                        exponent: None,
+                        inverse: false,
+                    }],
+                    transformation: TransformationSyntax::Linear {
+                        scale: "1".into(),
+                        offset: Some("273.15".into()),
+                    },
+                },
+                prefixes: vec![],
+                symbol: Some("°C".into()),
+                aliases: vec!["celsius".into()],
+            })
+        );
+        let StatementKind::Unit(arshin) = &statements[3].kind else {
+            panic!("Expected a unit, got {:?}", statements[3].kind);
+        };
+        assert_eq!(
+            arshin.definition,
+            DefinitionSyntax::Derived {
+                expression: "0.7112 meter".into()
+            }
+        );
+        assert_eq!(arshin.prefixes, vec!["russian".to_string()]);
+
+        let error = parse_file("unit meter {", "units.txt").unwrap_err();
+        assert!(error.message.contains("units.txt"));
+    }
+
+    #[test]
+    fn test_parse_dimensions_and_transformations() {
+        let statements = parse_file(
+            "unit newton {
+                dimension: mass * length / time^2 * count
+                transformation: identity
+                prefixes: standard, binary
+            }
+            unit neper {
+                dimension: count
+                transformation: log(base: e, factor: 0.5, gain: true)
+                prefixes: no
+            }
+            unit decibel_volt {
+                dimension: count
+                transformation: decibel(p0: 1, factor: 20)
+                prefixes: no
+            }
+            unit decibel {
+                dimension: count
+                transformation: decibel(p0: 1, gain: true)
+                prefixes: no
+            }",
+            "<string>",
+        )
+        .unwrap();
+        let definitions: Vec<&UnitSyntax> = statements
+            .iter()
+            .filter_map(|statement| match &statement.kind {
+                StatementKind::Unit(unit) => Some(unit),
+                _ => None,
+            })
+            .collect();
+
+        let DefinitionSyntax::Base { dimension, .. } = &definitions[0].definition else {
+            panic!("Expected a base unit");
+        };
+        let terms: Vec<(&str, Option<&str>, bool)> = dimension
+            .iter()
+            .map(|term| {
+                (
+                    term.fundamental.as_str(),
+                    term.exponent.as_deref(),
+                    term.inverse,
+                )
+            })
+            .collect();
+        assert_eq!(
+            terms,
+            vec![
+                ("mass", None, false),
+                ("length", None, false),
+                ("time", Some("2"), true),
+                ("count", None, false),
+            ]
+        );
+        assert_eq!(definitions[0].prefixes, vec!["standard", "binary"]);
+
+        let transformation = |unit: &UnitSyntax| match &unit.definition {
+            DefinitionSyntax::Base { transformation, .. } => transformation.clone(),
+            _ => panic!("Expected a base unit"),
+        };
+        assert_eq!(
+            transformation(definitions[1]),
+            TransformationSyntax::Logarithmic {
+                base: None,
+                factor: Some("0.5".into()),
+                reference: None,
+                gain: true,
+            }
+        );
+        assert_eq!(
+            transformation(definitions[2]),
+            TransformationSyntax::Decibel {
+                p0: "1".into(),
+                factor: Some("20".into()),
+                gain: false,
+            }
+        );
+        assert_eq!(
+            transformation(definitions[3]),
+            TransformationSyntax::Decibel {
+                p0: "1".into(),
+                factor: None,
+                gain: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_expression() {
+        let expression = parse_expression("kilogram * 2.5 meter / (second)^2").unwrap();
+        assert_eq!(expression.text, "kilogram * 2.5 meter / (second)^2");
+        assert_eq!(expression.first.primary, Primary::Name("kilogram".into()));
+        assert_eq!(expression.rest.len(), 2);
+
+        let (operator, scaled) = &expression.rest[0];
+        assert_eq!(*operator, Operator::Mul);
+        assert_eq!(scaled.primary, Primary::Number("2.5".into()));
+        assert!(matches!(
+            &scaled.suffix,
+            Some(Suffix::Factor(factor)) if factor.primary == Primary::Name("meter".into())
+        ));
+
+        let (operator, power) = &expression.rest[1];
+        assert_eq!(*operator, Operator::Div);
+        assert_eq!(power.text, "(second)^2");
+        assert!(matches!(&power.primary, Primary::Group(group) if group.text == "second"));
+        assert_eq!(power.suffix, Some(Suffix::Exponent("2".into())));
+
+        assert!(parse_expression("meter * ").is_err());
+    }
+
+    #[test]
+    fn test_split_magnitude() {
+        assert_eq!(split_magnitude("9.81 m/s^2"), Some(("9.81", " m/s^2")));
+        assert_eq!(
+            split_magnitude("-1.5E3kilometer"),
+            Some(("-1.5E3", "kilometer"))
+        );
+        assert_eq!(
+            split_magnitude(".5 degree_celsius"),
+            Some((".5", " degree_celsius"))
+        );
+        assert_eq!(split_magnitude("NaN meter"), Some(("NaN", " meter")));
+        assert_eq!(split_magnitude("inf"), Some(("inf", "")));
+        assert_eq!(split_magnitude("nanometer"), None);
+        assert_eq!(split_magnitude("inch"), None);
+        assert_eq!(split_magnitude("meter"), None);
+    }
+}
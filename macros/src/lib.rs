@@ -0,0 +1,213 @@
+//! Compile-time checked counterparts of the `u!` and `q!` macros of `arshin`, named `unit!` and
+//! `quantity!` so that both can be imported together.
+//!
+//! Unit names and expressions are resolved against the default registry while compiling, so a
+//! typo is a compile error (with a "did you mean" suggestion) instead of a runtime
+//! `RegistryDoesNotContainUnit`.
+
+use arshin::{ArshinError, DEFAULT_REGISTRY, Unit, UnitTransformation};
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{Expr, LitStr, Token, parse_macro_input};
+
+/// Gets a unit of the default registry by name or unit expression, checked at compile time.
+///
+/// Expands to a `Unit` constructor, so it can also initialize constants.
+///
+/// # Examples
+/// `unit!("meter")`, `unit!("km")`, `unit!("kilogram * meter / second^2")`,
+/// `const NEWTON: Unit = unit!("N");`.
+#[proc_macro]
+pub fn unit(input: TokenStream) -> TokenStream {
+    let expression = parse_macro_input!(input as LitStr);
+
+    match resolve_unit(&expression.value()).and_then(|unit| unit_tokens(&unit)) {
+        Ok(unit) => unit.into(),
+        Err(message) => syn::Error::new(expression.span(), message)
+            .to_compile_error()
+            .into(),
+    }
+}
+
+/// Creates a quantity from a value and a unit name or expression of the default registry,
+/// checked at compile time.
+///
+/// # Examples
+/// `quantity!(5.0, "meter")`, `quantity!(speed * 2.0, "km/hour")`.
+#[proc_macro]
+pub fn quantity(input: TokenStream) -> TokenStream {
+    let QuantityInput { magnitude, unit } = parse_macro_input!(input as QuantityInput);
+
+    match resolve_unit(&unit.value()).and_then(|unit| unit_tokens(&unit)) {
+        Ok(unit) => quote!(::arshin::Quantity::new(#magnitude, #unit)).into(),
+        Err(message) => syn::Error::new(unit.span(), message)
+            .to_compile_error()
+            .into(),
+    }
+}
+
+struct QuantityInput {
+    magnitude: Expr,
+    unit: LitStr,
+}
+
+impl Parse for QuantityInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let magnitude = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let unit = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+
+        Ok(Self { magnitude, unit })
+    }
+}
+
+fn resolve_unit(expression: &str) -> Result<Unit, String> {
+    DEFAULT_REGISTRY
+        .parse_unit(expression)
+        .map_err(|error| match error {
+            ArshinError::RegistryDoesNotContainUnit { name } => match suggest(&name) {
+                Some(suggestion) => {
+                    format!("unknown unit `{}`, did you mean `{}`?", name, suggestion)
+                }
+                None => format!("unknown unit `{}`", name),
+            },
+            error => error.to_string(),
+        })
+}
+
+/// Finds the closest unit name or alias of the default registry, if close enough.
+fn suggest(name: &str) -> Option<String> {
+    let max_distance = 1.max(name.chars().count() / 3);
+
+    DEFAULT_REGISTRY
+        .unit_names()
+        .chain(
+            DEFAULT_REGISTRY
+                .aliases()
+                .map(|(alias, _)| alias.to_string()),
+        )
+        .map(|candidate| (edit_distance(name, &candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance between two strings, in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+fn unit_tokens(unit: &Unit) -> Result<TokenStream2, String> {
+    let name = unit.name();
+    let symbol = match unit.symbol() {
+        Some(symbol) => quote!(::core::option::Option::Some(#symbol)),
+        None => quote!(::core::option::Option::None),
+    };
+    let powers = unit.dimensionality().powers();
+    let transformation = match unit.transformation() {
+        UnitTransformation::Identity => quote!(::arshin::UnitTransformation::Identity),
+        UnitTransformation::Linear(linear) => {
+            let scale = float_tokens(linear.scale());
+            let offset = float_tokens(linear.offset());
+            quote!(::arshin::UnitTransformation::Linear(
+                ::arshin::LinearTransformation::new(#scale, #offset)
+            ))
+        }
+        UnitTransformation::Logarithmic(logarithmic) if logarithmic.is_gain() => {
+            let base = float_tokens(logarithmic.base());
+            let factor = float_tokens(logarithmic.factor());
+            quote!(::arshin::UnitTransformation::Logarithmic(
+                ::arshin::LogarithmicTransformation::new_gain(#base, #factor)
+            ))
+        }
+        UnitTransformation::Logarithmic(logarithmic) => {
+            let base = float_tokens(logarithmic.base());
+            let factor = float_tokens(logarithmic.factor());
+            let reference = float_tokens(logarithmic.reference());
+            quote!(::arshin::UnitTransformation::Logarithmic(
+                ::arshin::LogarithmicTransformation::new(#base, #factor, #reference)
+            ))
+        }
+        UnitTransformation::Custom(_) => {
+            return Err(format!(
+                "unit `{}` has a custom transformation and cannot be built at compile time",
+                name
+            ));
+        }
+    };
+
+    if unit.factors().is_empty() {
+        return Ok(quote!(::arshin::Unit::new_static(
+            #name,
+            #symbol,
+            ::arshin::Dimension::new([#(#powers),*]),
+            #transformation
+        )));
+    }
+
+    // A constant item, so that the factors are `'static` outside of constants as well
+    let factors = unit
+        .factors()
+        .iter()
+        .map(|(factor, power)| {
+            let factor = unit_tokens(factor)?;
+            Ok(quote!((#factor, #power)))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(quote!({
+        const FACTORS: &[(::arshin::Unit, i64)] = &[#(#factors),*];
+        ::arshin::Unit::new_static_derived(
+            #name,
+            #symbol,
+            ::arshin::Dimension::new([#(#powers),*]),
+            #transformation,
+            FACTORS
+        )
+    }))
+}
+
+// Bit patterns keep non-finite values and do not trip lints on approximate constants (e.g. the
+// base `e` of nepers) in the caller's crate.
+fn float_tokens(value: f64) -> TokenStream2 {
+    let bits = value.to_bits();
+    quote!(f64::from_bits(#bits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("meter", "meter"), 0);
+        assert_eq!(edit_distance("metr", "meter"), 1);
+        assert_eq!(edit_distance("kilometre", "kilometer"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_resolve_unit() {
+        assert_eq!(resolve_unit("km").unwrap().name(), "kilometer");
+        assert_eq!(
+            resolve_unit("kilogram * meter / sekond^2"),
+            Err("unknown unit `sekond`, did you mean `second`?".into())
+        );
+        assert_eq!(resolve_unit("zzzzzz"), Err("unknown unit `zzzzzz`".into()));
+        assert!(resolve_unit("meter /").is_err());
+    }
+}
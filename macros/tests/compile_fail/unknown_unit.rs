@@ -0,0 +1,5 @@
+use arshin_macros::unit;
+
+fn main() {
+    let _ = unit!("meterr");
+}
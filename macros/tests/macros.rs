@@ -0,0 +1,53 @@
+use arshin::{DEFAULT_REGISTRY, Quantity, Unit};
+use arshin::{q, u};
+use arshin_macros::{quantity, unit};
+
+const NEWTON: Unit = unit!("N");
+const SPEED: Unit = unit!("km / hour");
+
+#[test]
+fn test_checked_unit() {
+    assert_eq!(&unit!("meter"), DEFAULT_REGISTRY.get("meter").unwrap());
+    assert_eq!(
+        unit!("km"),
+        *DEFAULT_REGISTRY.get_prefixed("kilometer").unwrap()
+    );
+    assert_eq!(
+        &unit!("°C"),
+        DEFAULT_REGISTRY.get("degree_celsius").unwrap()
+    );
+    assert_eq!(&unit!("Np"), DEFAULT_REGISTRY.get("neper").unwrap());
+    assert_eq!(NEWTON.name(), "newton");
+    assert_eq!(unit!("km"), u!("km").unwrap());
+
+    let force = unit!("kilogram * meter / second^2");
+    assert!(force.compatible(&NEWTON));
+    assert_eq!(force.to_base(1.0), 1.0);
+    assert_eq!(
+        force,
+        DEFAULT_REGISTRY
+            .parse_unit("kilogram * meter / second^2")
+            .unwrap()
+    );
+    assert_eq!(SPEED, DEFAULT_REGISTRY.parse_unit("km / hour").unwrap());
+    assert_eq!(SPEED.factors().len(), 2);
+}
+
+#[test]
+fn test_checked_quantity() {
+    let distance: Quantity<f64> = quantity!(5.0, "km");
+    assert_eq!(distance.m_as(&unit!("meter")), Ok(5000.0));
+    assert_eq!(
+        distance.base_magnitude(),
+        q!(5.0, "km").unwrap().base_magnitude()
+    );
+
+    let speed = 2.0;
+    let speed = quantity!(speed * 36.0, "km / hour");
+    assert!((speed.m_as(&unit!("meter / second")).unwrap() - 20.0).abs() < 1.0e-12);
+}
+
+#[test]
+fn test_compile_errors() {
+    trybuild::TestCases::new().compile_fail("tests/compile_fail/*.rs");
+}
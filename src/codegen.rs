@@ -0,0 +1,200 @@
+use crate::errors::ArshinError as Error;
+use crate::parser::{parse_units_from_path, transformation_entry};
+use crate::prefixes::PrefixSet;
+use crate::registry::UnitRegistry;
+use crate::units::Unit;
+use arshin_dsl::codegen::{PrefixSetEntry, Table, UnitEntry};
+use std::fs;
+use std::path::Path;
+
+/// Generates Rust source of a `pub static` [`StaticRegistry`](crate::registry::StaticRegistry)
+/// named `name` holding the units, aliases and prefix sets of `registry`.
+///
+/// The source refers to the crate as `::arshin`, so it can be `include!`d anywhere depending on it.
+///
+/// # Parameters
+/// - `registry`: Registry to compile.
+/// - `name`: Name of the generated static.
+///
+/// # Returns
+/// Rust source code.
+///
+/// # Errors
+/// `CustomUnitOperation` if a unit has a custom transformation, which cannot be written as code.
+pub fn generate(registry: &UnitRegistry, name: &str) -> Result<String, Error> {
+    let builtin = [PrefixSet::standard(), PrefixSet::binary()];
+    let table = Table {
+        units: registry.units().map(unit_entry).collect::<Result<_, _>>()?,
+        aliases: registry
+            .aliases()
+            .map(|(alias, name)| (alias.to_string(), name.to_string()))
+            .collect(),
+        prefixable: registry.prefixable().into_iter().collect(),
+        prefix_sets: registry
+            .prefix_sets()
+            .iter()
+            .filter(|set| !builtin.contains(set))
+            .map(|set| PrefixSetEntry {
+                name: set.name().into(),
+                prefixes: set
+                    .prefixes()
+                    .iter()
+                    .map(|prefix| (prefix.name.clone(), prefix.symbol.clone(), prefix.factor))
+                    .collect(),
+            })
+            .collect(),
+    };
+
+    Ok(table.generate(name))
+}
+
+/// Parses a units file (resolving imports) and writes the [`generate`]d source to `out_file`.
+///
+/// Meant for build scripts:
+///
+/// ```ignore
+/// // build.rs
+/// let out_file = Path::new(&env::var("OUT_DIR").unwrap()).join("company_units.rs");
+/// arshin::codegen::write_registry("units/company.txt", "COMPANY_UNITS", out_file).unwrap();
+/// println!("cargo::rerun-if-changed=units");
+///
+/// // src/lib.rs
+/// include!(concat!(env!("OUT_DIR"), "/company_units.rs"));
+/// let registry = UnitRegistry::from_static(&COMPANY_UNITS);
+/// ```
+///
+/// # Errors
+/// - Units file errors (see [`parse_units_from_path`]).
+/// - Write errors.
+pub fn write_registry(
+    units_file: impl AsRef<Path>,
+    name: &str,
+    out_file: impl AsRef<Path>,
+) -> Result<(), Error> {
+    let registry = parse_units_from_path(units_file)?;
+    fs::write(out_file.as_ref(), generate(&registry, name)?).map_err(|e| Error::OSError {
+        message: format!("{}: {}", out_file.as_ref().display(), e),
+    })
+}
+
+fn unit_entry(unit: &Unit) -> Result<UnitEntry, Error> {
+    let transformation =
+        transformation_entry(unit.transformation()).ok_or_else(|| Error::CustomUnitOperation {
+            name: unit.name().into(),
+            operation: "code generation".into(),
+        })?;
+
+    Ok(UnitEntry {
+        name: unit.name().into(),
+        symbol: unit.symbol().map(String::from),
+        dimension: unit.dimensionality().powers(),
+        transformation,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fundamentals::base::LENGTH;
+    use crate::parser::parse_units_file;
+    use crate::transformations::UnitConversion;
+
+    #[test]
+    fn test_generate() {
+        let registry = parse_units_file(
+            r#"
+            prefix_set russian { pol = 0.5 }
+            unit arshin {
+                dimension: length
+                transformation: linear(scale: 0.7112)
+                prefixes: russian
+                symbol: ar
+            }
+            "#,
+        )
+        .unwrap();
+
+        let source = generate(&registry, "RUSSIAN_UNITS").unwrap();
+        assert!(source.starts_with("/// Static unit tables"));
+        assert!(source.contains("pub static RUSSIAN_UNITS: ::arshin::registry::StaticRegistry"));
+        assert!(source.contains(
+            "::arshin::Unit::new_static(\"arshin\", Some(\"ar\"), \
+             ::arshin::Dimension::new([0, 1, 0, 0, 0, 0, 0, 0, 0, 0]), \
+             ::arshin::UnitTransformation::Linear(::arshin::LinearTransformation::new(0.7112f64, 0.0f64)))"
+        ));
+        assert!(source.contains("prefix_sets: &[(\"russian\", &[(\"pol\", \"\", 0.5f64)])]"));
+        assert!(!source.contains("\"kilo\""));
+    }
+
+    #[derive(Debug)]
+    struct Cube;
+
+    impl UnitConversion for Cube {
+        fn to_base(&self, value: f64) -> f64 {
+            value.powi(3)
+        }
+
+        fn from_base(&self, value: f64) -> f64 {
+            value.cbrt()
+        }
+    }
+
+    #[test]
+    fn test_generate_custom_unit() {
+        let mut registry = UnitRegistry::new();
+        registry
+            .register(Unit::new_custom("cube_meter", LENGTH, Cube))
+            .unwrap();
+
+        assert_eq!(
+            generate(&registry, "UNITS"),
+            Err(Error::CustomUnitOperation {
+                name: "cube_meter".into(),
+                operation: "code generation".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_build_script_tables() {
+        // The build script compiles units files without a registry; both must agree
+        let content = r#"
+            prefix_set russian { pol(pl) = 0.5 chetvert = 0.25 }
+            unit meter {
+                dimension: length
+                transformation: identity
+                prefixes: standard, russian
+                symbol: m
+                aliases: [metre]
+            }
+            unit second { dimension: time transformation: identity prefixes: standard symbol: s }
+            unit degree_celsius {
+                dimension: temperature
+                transformation: linear(scale: 1, offset: 273.15)
+                prefixes: no
+                symbol: °C
+                aliases: [celsius]
+            }
+            unit neper { dimension: count transformation: log(base: e, factor: 0.5, gain: true) prefixes: no symbol: Np }
+            unit decibel_volt { dimension: count transformation: decibel(p0: 1, factor: 20) prefixes: no }
+            unit decibel { dimension: count transformation: decibel(p0: 1, gain: true) prefixes: no }
+            unit arshin = 1.4224 polmetre { symbol: ar }
+            unit knot = 1852 m / (60 * 60 s)
+            unit per_area = (pm)^-2
+            unit hertz = 1 / second { prefixes: standard symbol: Hz }
+        "#;
+
+        let table = Table::from_units(content).unwrap();
+        let registry = parse_units_file(content).unwrap();
+        assert_eq!(
+            table.generate("UNITS"),
+            generate(&registry, "UNITS").unwrap()
+        );
+
+        let table = Table::from_units(crate::DEFAULT_UNITS).unwrap();
+        assert_eq!(
+            table.generate("UNITS"),
+            generate(&UnitRegistry::default(), "UNITS").unwrap()
+        );
+    }
+}
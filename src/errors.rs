@@ -1,4 +1,4 @@
-use crate::fundamentals::Dimension;
+use crate::fundamentals::{Dimension, Fundamentals};
 use thiserror::Error;
 
 /// Errors for the Arshin library.
@@ -27,4 +27,94 @@ pub enum ArshinError {
 
     #[error("Registry does not contain unit {}", name)]
     RegistryDoesNotContainUnit { name: String },
+
+    #[error(
+        "Unit {} with biased, logarithmic or custom transformation does not accept prefixes",
+        name
+    )]
+    IncompatiblePrefixes { name: String },
+
+    #[error("Prefix set {} already exists", name)]
+    RegistryAlreadyContainsPrefixSet { name: String },
+
+    #[error("Unknown prefix set {}", name)]
+    UnknownPrefixSet { name: String },
+
+    #[error("{} defined in {} is already defined in {}", name, second, first)]
+    DuplicateDefinition {
+        name: String,
+        first: String,
+        second: String,
+    },
+
+    #[error("Import cycle: {}", files.join(" -> "))]
+    ImportCycle { files: Vec<String> },
+
+    #[error("Prefix {} is defined more than once for {}", prefix, name)]
+    PrefixCollision { name: String, prefix: String },
+
+    #[error("Invalid definition: {}", message)]
+    InvalidDefinition { message: String },
+
+    #[error("{}:{}:{}: in definition of {}: {}", file, line, column, name, error)]
+    DefinitionError {
+        file: String,
+        line: usize,
+        column: usize,
+        name: String,
+        error: Box<ArshinError>,
+    },
+
+    #[error("Definition of unit {} refers to undefined unit {}", unit, reference)]
+    UndefinedUnitReference { unit: String, reference: String },
+
+    #[error("Invalid unit expression {}: {}", expression, message)]
+    InvalidUnitExpression { expression: String, message: String },
+
+    #[error(
+        "Cannot apply {} to unit {} with biased transformation",
+        operation,
+        name
+    )]
+    BiasedUnitOperation { name: String, operation: String },
+
+    #[error(
+        "Cannot apply {} to unit {} with logarithmic transformation",
+        operation,
+        name
+    )]
+    LogarithmicUnitOperation { name: String, operation: String },
+
+    #[error(
+        "Cannot apply {} to unit {} with custom transformation",
+        operation,
+        name
+    )]
+    CustomUnitOperation { name: String, operation: String },
+
+    #[error(
+        "Transformation is not invertible: {} converts back to {}",
+        value,
+        result
+    )]
+    NonInvertibleTransformation { value: f64, result: f64 },
+
+    #[error("Unit system {} has no base unit for {}", system, fundamental)]
+    MissingBaseUnit {
+        system: String,
+        fundamental: Fundamentals,
+    },
+
+    #[error(
+        "Unit {} cannot be a base unit of system {}: only units without offset, logarithm or custom transformation can",
+        name,
+        system
+    )]
+    InvalidBaseUnit { system: String, name: String },
+
+    #[error("Cannot raise unit {} to zero power", name)]
+    ZeroPower { name: String },
+
+    #[error("Invalid quantity {}: {}", input, message)]
+    InvalidQuantity { input: String, message: String },
 }
@@ -0,0 +1,368 @@
+use crate::fundamentals::base::COUNT;
+use crate::quantities::Quantity;
+use crate::units::Unit;
+use std::fmt::{self, Write};
+
+/// Output style of formatted units and quantities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FormatStyle {
+    /// Plain ASCII: `m/s^2`, `kg*m^2/(s^2*K)`.
+    #[default]
+    Plain,
+    /// Unicode superscripts and middle dots: `m·s⁻²`.
+    Unicode,
+    /// LaTeX math: `\mathrm{m\,s^{-2}}`.
+    Latex,
+    /// HTML: `m s<sup>-2</sup>`.
+    Html,
+}
+
+/// How to format a unit or quantity: style, long names or symbols, and whether to rescale the
+/// quantity to its best prefix first (see [`Quantity::to_compact`]).
+///
+/// # Examples
+/// `FormatSpec::new(FormatStyle::Latex).with_long_names().with_compact()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FormatSpec {
+    pub style: FormatStyle,
+    pub long_names: bool,
+    pub compact: bool,
+}
+
+impl FormatSpec {
+    pub fn new(style: FormatStyle) -> Self {
+        Self {
+            style,
+            ..Self::default()
+        }
+    }
+
+    /// Uses unit names (`meter`) instead of symbols (`m`).
+    pub fn with_long_names(mut self) -> Self {
+        self.long_names = true;
+        self
+    }
+
+    /// Rescales quantities to the best prefix before formatting (e.g. `12 µs`).
+    pub fn with_compact(mut self) -> Self {
+        self.compact = true;
+        self
+    }
+}
+
+/// Formats a unit, e.g. `m/s^2` or `\mathrm{m\,s^{-2}}`.
+///
+/// Units built from others (e.g. `kilogram * meter / second^2`) are written factor by factor,
+/// with the symbols of their factors, falling back to names.
+pub(crate) fn format_unit(unit: &Unit, spec: &FormatSpec) -> String {
+    let label = |unit: &Unit| match unit.symbol() {
+        Some(symbol) if !spec.long_names => symbol.to_string(),
+        _ => unit.name().to_string(),
+    };
+    let factors: Vec<(String, i64)> = unit
+        .reduced_factors()
+        .iter()
+        .map(|(factor, power)| (escape(&label(factor), spec.style), *power))
+        .collect();
+
+    match spec.style {
+        FormatStyle::Plain => plain_unit(&factors),
+        FormatStyle::Unicode => factors
+            .iter()
+            .map(|(label, power)| match power {
+                1 => label.clone(),
+                _ => format!("{}{}", label, superscript(*power)),
+            })
+            .collect::<Vec<_>>()
+            .join("·"),
+        FormatStyle::Latex => format!(
+            "\\mathrm{{{}}}",
+            factors
+                .iter()
+                .map(|(label, power)| match power {
+                    1 => label.clone(),
+                    _ => format!("{}^{{{}}}", label, power),
+                })
+                .collect::<Vec<_>>()
+                .join("\\,")
+        ),
+        FormatStyle::Html => factors
+            .iter()
+            .map(|(label, power)| match power {
+                1 => label.clone(),
+                _ => format!("{}<sup>{}</sup>", label, power),
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+/// Quantity formatted with a [`FormatSpec`]; honours the precision and `{:e}` of the format
+/// string (e.g. `format!("{:.2e}", quantity.display(spec))`).
+pub struct QuantityDisplay<'a> {
+    pub(crate) quantity: &'a Quantity<f64>,
+    pub(crate) spec: FormatSpec,
+}
+
+impl QuantityDisplay<'_> {
+    fn write(&self, f: &mut fmt::Formatter<'_>, exponential: bool) -> fmt::Result {
+        let compact;
+        let quantity = if self.spec.compact {
+            compact = self.quantity.to_compact();
+            &compact
+        } else {
+            self.quantity
+        };
+
+        let value = quantity.unit().from_base(quantity.base_magnitude());
+        let number = match (f.precision(), exponential) {
+            (Some(precision), false) => format!("{:.*}", precision, value),
+            (None, false) => format!("{}", value),
+            (Some(precision), true) => format!("{:.*e}", precision, value),
+            (None, true) => format!("{:e}", value),
+        };
+        let mut text = format_number(&number, self.spec.style);
+
+        if !is_unitless(quantity.unit()) {
+            text.push_str(match self.spec.style {
+                FormatStyle::Latex => "\\ ",
+                _ => " ",
+            });
+            text.push_str(&format_unit(quantity.unit(), &self.spec));
+        }
+        pad(f, &text)
+    }
+}
+
+/// Checks if a unit is written as nothing after the number: a ratio of the same units (e.g.
+/// `meter / meter`) or any other dimensionless unit of scale 1 (e.g. the reduced ratio `1`).
+fn is_unitless(unit: &Unit) -> bool {
+    unit.reduced_factors().is_empty()
+        || (*unit.dimensionality() == COUNT && unit.scale() == Some(1.0))
+}
+
+/// Pads `text` to the width of the format string, with its fill and alignment (left by default).
+///
+/// Unlike [`fmt::Formatter::pad`], which would also cut `text` to the precision meant for the
+/// number.
+fn pad(f: &mut fmt::Formatter<'_>, text: &str) -> fmt::Result {
+    let padding = f
+        .width()
+        .map_or(0, |width| width.saturating_sub(text.chars().count()));
+    let (before, after) = match f.align() {
+        Some(fmt::Alignment::Right) => (padding, 0),
+        Some(fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+        _ => (0, padding),
+    };
+
+    let fill = f.fill();
+    for _ in 0..before {
+        f.write_char(fill)?;
+    }
+    f.write_str(text)?;
+    for _ in 0..after {
+        f.write_char(fill)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for QuantityDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, false)
+    }
+}
+
+impl fmt::LowerExp for QuantityDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, true)
+    }
+}
+
+/// `kg*m/s^2`, with parentheses around several denominator factors (`W/(m^2*K)`).
+fn plain_unit(factors: &[(String, i64)]) -> String {
+    let product = |factors: Vec<String>| factors.join("*");
+    let term = |label: &str, power: i64| match power {
+        1 => label.to_string(),
+        _ => format!("{}^{}", label, power),
+    };
+
+    let numerator: Vec<String> = factors
+        .iter()
+        .filter(|(_, power)| *power > 0)
+        .map(|(label, power)| term(label, *power))
+        .collect();
+    let denominator: Vec<String> = factors
+        .iter()
+        .filter(|(_, power)| *power < 0)
+        .map(|(label, power)| term(label, -power))
+        .collect();
+
+    match (numerator.is_empty(), denominator.len()) {
+        (_, 0) => product(numerator),
+        (true, _) => factors
+            .iter()
+            .map(|(label, power)| term(label, *power))
+            .collect::<Vec<_>>()
+            .join("*"),
+        (false, 1) => format!("{}/{}", product(numerator), product(denominator)),
+        (false, _) => format!("{}/({})", product(numerator), product(denominator)),
+    }
+}
+
+/// Writes the exponent of a number formatted with `{:e}` in the notation of the style
+/// (`1.2e-5` → `1.2×10⁻⁵`, `1.2\times10^{-5}`, `1.2×10<sup>-5</sup>`).
+fn format_number(number: &str, style: FormatStyle) -> String {
+    let Some((mantissa, exponent)) = number.split_once('e') else {
+        return number.to_string();
+    };
+    let Ok(exponent) = exponent.parse::<i64>() else {
+        return number.to_string();
+    };
+
+    match style {
+        FormatStyle::Plain => number.to_string(),
+        FormatStyle::Unicode => format!("{}×10{}", mantissa, superscript(exponent)),
+        FormatStyle::Latex => format!("{}\\times10^{{{}}}", mantissa, exponent),
+        FormatStyle::Html => format!("{}×10<sup>{}</sup>", mantissa, exponent),
+    }
+}
+
+fn superscript(power: i64) -> String {
+    power
+        .to_string()
+        .chars()
+        .map(|c| match c {
+            '-' => '⁻',
+            '0' => '⁰',
+            '1' => '¹',
+            '2' => '²',
+            '3' => '³',
+            '4' => '⁴',
+            '5' => '⁵',
+            '6' => '⁶',
+            '7' => '⁷',
+            '8' => '⁸',
+            '9' => '⁹',
+            c => c,
+        })
+        .collect()
+}
+
+/// Escapes characters with a special meaning in LaTeX and HTML.
+fn escape(label: &str, style: FormatStyle) -> String {
+    match style {
+        FormatStyle::Latex => label
+            .replace('\\', "\\backslash ")
+            .replace('_', "\\_")
+            .replace('%', "\\%")
+            .replace('$', "\\$"),
+        FormatStyle::Html => label
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;"),
+        _ => label.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ArshinError as Error;
+    use crate::{q, u};
+
+    #[test]
+    fn test_format_unit() -> Result<(), Error> {
+        let acceleration = u!("meter")? / u!("second")?.pow(2);
+        let spec = |style| FormatSpec::new(style);
+
+        assert_eq!(acceleration.format(&spec(FormatStyle::Plain)), "m/s^2");
+        assert_eq!(acceleration.format(&spec(FormatStyle::Unicode)), "m·s⁻²");
+        assert_eq!(
+            acceleration.format(&spec(FormatStyle::Latex)),
+            "\\mathrm{m\\,s^{-2}}"
+        );
+        assert_eq!(
+            acceleration.format(&spec(FormatStyle::Html)),
+            "m s<sup>-2</sup>"
+        );
+        assert_eq!(
+            acceleration.format(&spec(FormatStyle::Plain).with_long_names()),
+            "meter/second^2"
+        );
+
+        let conductance = u!("watt")? / (u!("meter")?.pow(2) * u!("delta_degree_celsius")?);
+        assert_eq!(conductance.format(&spec(FormatStyle::Plain)), "W/(m^2*Δ°C)");
+        assert_eq!(
+            conductance.format(&spec(FormatStyle::Latex).with_long_names()),
+            "\\mathrm{watt\\,meter^{-2}\\,delta\\_degree\\_celsius^{-1}}"
+        );
+        assert_eq!(u!("hertz")?.format(&spec(FormatStyle::Plain)), "Hz");
+        assert_eq!(
+            (u!("meter")? / u!("second")?)
+                .checked_div(&u!("meter")?)?
+                .reduced()
+                .format(&spec(FormatStyle::Plain)),
+            "s^-1"
+        );
+        assert_eq!(u!("arshin")?.format(&spec(FormatStyle::Plain)), "arshin");
+
+        // Symbols are the factors' own, not those of the default registry
+        let meter = Unit::new_base("meter", crate::fundamentals::base::LENGTH).with_symbol("M");
+        assert_eq!(
+            (meter / u!("second")?).format(&spec(FormatStyle::Plain)),
+            "M/s"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_quantity() -> Result<(), Error> {
+        let acceleration = q!(9.80665, "meter")? / q!(1.0, "second")?.pow(2);
+
+        assert_eq!(format!("{}", acceleration), "9.80665 m/s^2");
+        assert_eq!(format!("{:.3}", acceleration), "9.807 m/s^2");
+        assert_eq!(format!("{:.2e}", acceleration), "9.81e0 m/s^2");
+        assert_eq!(format!("{:#.1}", q!(0.000012, "second")?), "12.0 µs");
+        assert_eq!(format!("{:#.1}", q!(3.2e9, "byte")?), "3.2 GB");
+        assert_eq!(format!("{}", q!(20.0, "degree_celsius")?), "20 °C");
+
+        let spec = FormatSpec::new(FormatStyle::Unicode);
+        assert_eq!(format!("{:.1}", acceleration.display(spec)), "9.8 m·s⁻²");
+        assert_eq!(
+            format!("{:.1e}", q!(0.000012, "second")?.display(spec)),
+            "1.2×10⁻⁵ s"
+        );
+        assert_eq!(
+            format!(
+                "{:.1e}",
+                acceleration.display(FormatSpec::new(FormatStyle::Latex))
+            ),
+            "9.8\\times10^{0}\\ \\mathrm{m\\,s^{-2}}"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                q!(5.0, "km")?.display(FormatSpec::new(FormatStyle::Html).with_long_names())
+            ),
+            "5 kilometer"
+        );
+
+        let ratio = q!(3.0, "meter")? / q!(1.5, "meter")?;
+        assert_eq!(format!("{}", ratio), "2");
+        assert_eq!(format!("[{:>3}]", ratio), "[  2]");
+        assert_eq!(
+            format!("{}", ratio.display(FormatSpec::new(FormatStyle::Latex))),
+            "2"
+        );
+        assert_eq!(format!("{}", ratio.to_reduced_units()), "2");
+        assert_eq!(format!("{}", q!(5.0, "percent")?), "5 percent");
+
+        // Width, fill and alignment apply to the whole quantity
+        assert_eq!(format!("[{:>8.1}]", q!(5.0, "km")?), "[  5.0 km]");
+        assert_eq!(format!("[{:*<8}]", q!(5.0, "km")?), "[5 km****]");
+        assert_eq!(format!("[{:^8}]", q!(5.0, "km")?), "[  5 km  ]");
+
+        Ok(())
+    }
+}
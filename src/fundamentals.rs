@@ -93,6 +93,7 @@ pub type FundamentalsPowersType = i32;
 /// assert_eq!(force_dim.to_string(), "mass * length * [time]^-2");
 /// ```
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Dimension([FundamentalsPowersType; FUNDAMENTALS_NUMBER]);
 
 impl Dimension {
@@ -105,19 +106,28 @@ impl Dimension {
     ///
     /// # Returns
     /// A new `Dimension`.
-    pub fn new(powers: [FundamentalsPowersType; FUNDAMENTALS_NUMBER]) -> Dimension {
+    pub const fn new(powers: [FundamentalsPowersType; FUNDAMENTALS_NUMBER]) -> Dimension {
         let mut result = [0; FUNDAMENTALS_NUMBER];
-        if powers[..FUNDAMENTALS_NUMBER - 1] == [0; FUNDAMENTALS_NUMBER - 1] {
-            result[FUNDAMENTALS_NUMBER - 1..].copy_from_slice(&[1]);
-            Dimension(result)
-        } else {
-            result[..FUNDAMENTALS_NUMBER - 1].copy_from_slice(&powers[..FUNDAMENTALS_NUMBER - 1]);
-            result[FUNDAMENTALS_NUMBER - 1..].copy_from_slice(&[0]);
-            Dimension(result)
+        let mut dimensionless = true;
+        let mut i = 0;
+        while i < FUNDAMENTALS_NUMBER - 1 {
+            dimensionless &= powers[i] == 0;
+            result[i] = powers[i];
+            i += 1;
         }
+        if dimensionless {
+            result[FUNDAMENTALS_NUMBER - 1] = 1;
+        }
+
+        Dimension(result)
+    }
+
+    /// Exponents for each fundamental, in the order accepted by [`Dimension::new`].
+    pub const fn powers(&self) -> [FundamentalsPowersType; FUNDAMENTALS_NUMBER] {
+        self.0
     }
 
-    const fn new_from_fundamental(fundamental: Fundamentals) -> Dimension {
+    pub(crate) const fn new_from_fundamental(fundamental: Fundamentals) -> Dimension {
         let mut powers = [0; 10];
         powers[fundamental.to_index()] = 1;
 
@@ -1,9 +1,21 @@
+// Lets generated static tables refer to `::arshin` inside this crate too.
+extern crate self as arshin;
+
+#[cfg(feature = "codegen")]
+pub mod codegen;
 pub mod errors;
+pub mod formatting;
 pub mod fundamentals;
 pub mod parser;
+pub mod prefixes;
 pub mod quantities;
 pub mod registry;
+#[cfg(feature = "serde")]
+pub mod serialization;
+pub mod systems;
 pub mod transformations;
+#[cfg(feature = "typed")]
+pub mod typed;
 pub mod units;
 
 pub use errors::ArshinError;
@@ -11,8 +23,11 @@ pub use transformations::{
     MathOpsF64,
     UnitTransformation,
     LinearTransformation,
-    DecibelTransformation,
+    LogarithmicTransformation,
+    UnitConversion,
 };
+#[allow(deprecated)]
+pub use transformations::DecibelTransformation;
 pub use fundamentals::{
     Fundamentals,
     FUNDAMENTALS_NUMBER,
@@ -20,6 +35,12 @@ pub use fundamentals::{
     Dimension,
 };
 pub use units::Unit;
+pub use prefixes::{Prefix, PrefixSet};
 pub use quantities::Quantity;
-pub use registry::{UnitRegistry, DEFAULT_REGISTRY};
-pub use parser::parse_units_file;
\ No newline at end of file
+pub use formatting::{FormatSpec, FormatStyle};
+pub use systems::UnitSystem;
+pub use registry::{UnitRegistry, ConflictPolicy, StaticRegistry, DEFAULT_REGISTRY, DEFAULT_TABLE, DEFAULT_UNITS};
+pub use parser::{parse_quantity, parse_unit_expression, parse_units_file, parse_units_from_path};
+
+#[doc(hidden)]
+pub use phf;
\ No newline at end of file
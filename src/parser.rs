@@ -1,77 +1,43 @@
 use crate::errors::ArshinError as Error;
+use crate::fundamentals::Dimension;
 use crate::fundamentals::base::*;
-use crate::registry::UnitRegistry;
-use crate::transformations::{DecibelTransformation, UnitTransformation};
+use crate::prefixes::{Prefix, PrefixSet};
+use crate::quantities::Quantity;
+use crate::registry::{ConflictPolicy, UnitRegistry};
+use crate::transformations::{LinearTransformation, LogarithmicTransformation, UnitTransformation};
 use crate::units::Unit;
-use pest::{Parser, iterators::Pair};
-use pest_derive::Parser;
-
-#[derive(Parser)]
-#[grammar = "units.pest"]
-struct UnitsParser;
-
-#[derive(Debug)]
-struct UnitDefinition {
-    pub name: String,
-    pub dimension: DimensionExpression,
-    pub transformation: Transformation,
-    pub prefixes: Prefixes,
-}
-
-#[derive(Debug)]
-struct DimensionExpression {
-    pub terms: Vec<DimensionTerm>,
-}
-
-#[derive(Debug)]
-struct DimensionTerm {
-    pub fundamental: String,
-    pub exponent: i64,
-}
-
-#[derive(Debug)]
-enum Transformation {
-    Identity,
-    Linear { scale: f64, offset: Option<f64> },
-    Decibel { p0: f64 },
-}
-
-#[derive(Debug, PartialEq, Eq)]
-enum Prefixes {
-    Standard,
-    No,
-}
-
-const SI_PREFIXES: [(&str, &str, f64); 24] = [
-    ("Quetta", "Q", 1e30),
-    ("Ronna", "R", 1e27),
-    ("Yotta", "Y", 1e24),
-    ("Zetta", "Z", 1e21),
-    ("Exa", "E", 1e18),
-    ("Peta", "P", 1e15),
-    ("Tera", "T", 1e12),
-    ("Giga", "G", 1e9),
-    ("Mega", "M", 1e6),
-    ("kilo", "k", 1e3),
-    ("hecto", "h", 1e2),
-    ("deca", "da", 1e1),
-    ("deci", "d", 1e-1),
-    ("centi", "c", 1e-2),
-    ("milli", "m", 1e-3),
-    ("micro", "µ", 1e-6),
-    ("nano", "n", 1e-9),
-    ("pico", "p", 1e-12),
-    ("femto", "f", 1e-15),
-    ("atto", "a", 1e-18),
-    ("zepto", "z", 1e-21),
-    ("yocto", "y", 1e-24),
-    ("ronto", "r", 1e-27),
-    ("quecto", "q", 1e-30),
-];
+use arshin_dsl::codegen::TransformationEntry;
+use arshin_dsl::evaluate::{self, EvaluationError, Powers, Scope, UnitValue};
+use arshin_dsl::syntax::{self, DefinitionSyntax, PrefixSetSyntax, StatementKind, UnitSyntax};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
 
 /// Parses a units file into a registry.
 ///
-/// Uses Pest grammar for definitions like: unit meter { dimension: length, transformation: identity, prefixes: standard }.
+/// Uses the grammar of `arshin-dsl` for definitions like: unit meter { dimension: length, transformation: identity, prefixes: standard }.
+/// Optional `symbol: m` and `aliases: [metre, meters]` properties register extra names for the
+/// unit; with `prefixes: standard` (SI), `prefixes: binary` (IEC) or `prefixes: standard, binary`
+/// the registry also resolves prefixed variants of them on lookup (e.g. `km`, `kilometre`, `KiB`).
+///
+/// Custom prefix sets are declared with `prefix_set name { kilo(k) = 1e3 mega(M) = 1e6 }`
+/// (symbols are optional) and referenced by name, e.g. `prefixes: standard, name`.
+///
+/// Units can also be defined relative to units defined earlier in the file, e.g.
+/// `unit inch = 2.54 centimeter` or `unit joule = newton * meter { prefixes: standard }`.
+///
+/// Logarithmic units are declared with `log(base: e, factor: 0.5, ref: 1)` (`factor` and `ref`
+/// default to 1), or with the `decibel(p0: 1e-3)` shorthand for `log(base: 10, factor: 10,
+/// ref: 1e-3)`; root-power levels such as dBV declare `decibel(p0: 1, factor: 20)`.
+/// Dimensionless units measuring gains (power ratios), which shift levels, declare
+/// `gain: true`, e.g. `decibel(p0: 1, gain: true)`; their reference must be 1.
+///
+/// Biased units (`linear(scale: 1.0, offset: 273.15)`) also get a delta unit for temperature
+/// differences, e.g. `delta_degree_celsius` with symbol `Δ°C` and alias `delta_celsius`.
+///
+/// `import "path"` directives are resolved relative to the current directory; use
+/// [`parse_units_from_path`] to resolve them relative to a file.
 ///
 /// # Parameters
 /// - `file_content`: String content of the file.
@@ -81,257 +47,441 @@ const SI_PREFIXES: [(&str, &str, f64); 24] = [
 ///
 /// # Errors
 /// - Invalid syntax.
-/// - Incompatible prefixes (e.g., with offsets/decibels).
+/// - Import errors.
+/// - `DefinitionError` with the file, line, column and name of the offending definition,
+///   wrapping e.g. duplicate definitions, incompatible prefixes (e.g., with offsets/decibels),
+///   prefix collisions, references to undefined units, a zero scale, an invalid logarithm base,
+///   factor or reference (e.g. a non-positive decibel `p0`), or an invalid exponent.
 pub fn parse_units_file(file_content: &str) -> Result<UnitRegistry, Error> {
     let mut registry = UnitRegistry::new();
-    let pairs =
-        UnitsParser::parse(Rule::units_list, file_content).map_err(|e| Error::PestParseError {
-            message: e.to_string(),
-        })?;
+    load_units_str(&mut registry, file_content, ConflictPolicy::Error)?;
+    Ok(registry)
+}
 
-    let mut units = Vec::new();
-    for pair in pairs {
-        match pair.as_rule() {
-            Rule::unit_definition => {
-                units.push(parse_unit_definition(pair));
-            }
-            _ => continue,
+/// Parses a units file and the files it imports into a registry.
+///
+/// `import "path"` (or `include "path"`) directives are resolved relative to the importing
+/// file. Files imported more than once are loaded once.
+///
+/// # Parameters
+/// - `path`: Path to the file.
+///
+/// # Returns
+/// `Ok(UnitRegistry)` or error.
+///
+/// # Errors
+/// - File read errors.
+/// - Import cycles.
+/// - Duplicate definitions (naming both files).
+/// - Parse errors (see [`parse_units_file`]).
+pub fn parse_units_from_path(path: impl AsRef<Path>) -> Result<UnitRegistry, Error> {
+    let mut registry = UnitRegistry::new();
+    load_units_path(&mut registry, path.as_ref(), ConflictPolicy::Error)?;
+    Ok(registry)
+}
+
+/// Parses units file content into an existing registry (see [`UnitRegistry::load_str`]).
+pub(crate) fn load_units_str(
+    registry: &mut UnitRegistry,
+    file_content: &str,
+    policy: ConflictPolicy,
+) -> Result<(), Error> {
+    UnitsLoader::new(registry, policy).load_str(file_content, "<string>", Path::new("."))
+}
+
+/// Parses a units file into an existing registry (see [`UnitRegistry::load_file`]).
+pub(crate) fn load_units_path(
+    registry: &mut UnitRegistry,
+    path: &Path,
+    policy: ConflictPolicy,
+) -> Result<(), Error> {
+    UnitsLoader::new(registry, policy).load_path(path)
+}
+
+/// Loading state: target registry, conflict policy, import stack and the file each name was
+/// defined in.
+struct UnitsLoader<'a> {
+    registry: &'a mut UnitRegistry,
+    policy: ConflictPolicy,
+    stack: Vec<PathBuf>,
+    loaded: HashSet<PathBuf>,
+    unit_origins: HashMap<String, String>,
+    prefix_set_origins: HashMap<String, String>,
+}
+
+impl<'a> UnitsLoader<'a> {
+    fn new(registry: &'a mut UnitRegistry, policy: ConflictPolicy) -> Self {
+        Self {
+            registry,
+            policy,
+            stack: Vec::new(),
+            loaded: HashSet::new(),
+            unit_origins: HashMap::new(),
+            prefix_set_origins: HashMap::new(),
         }
     }
 
-    for one in units {
-        let mut dimension = COUNT;
-        for term in one.dimension.terms {
-            let another_dimension = match term.fundamental.as_str() {
-                "length" => LENGTH,
-                "mass" => MASS,
-                "time" => TIME,
-                "current" => CURRENT,
-                "temperature" => TEMPERATURE,
-                "amount of substance" => AMOUNT_OF_SUBSTANCE,
-                "luminosity" => LUMINOSITY,
-                "angle" => ANGLE,
-                "bit" => BIT,
-                "count" => COUNT,
-                _ => unreachable!(),
+    fn load_path(&mut self, path: &Path) -> Result<(), Error> {
+        let os_error = |e: std::io::Error| Error::OSError {
+            message: format!("{}: {}", path.display(), e),
+        };
+
+        let canonical = fs::canonicalize(path).map_err(os_error)?;
+        if self.stack.contains(&canonical) {
+            return Err(Error::ImportCycle {
+                files: self
+                    .stack
+                    .iter()
+                    .chain(std::iter::once(&canonical))
+                    .map(|file| file.display().to_string())
+                    .collect(),
+            });
+        }
+        if !self.loaded.insert(canonical.clone()) {
+            return Ok(());
+        }
+
+        let content = fs::read_to_string(&canonical).map_err(os_error)?;
+        let directory = canonical
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        self.stack.push(canonical);
+        let result = self.load_str(&content, &path.display().to_string(), &directory);
+        self.stack.pop();
+
+        result
+    }
+
+    fn load_str(&mut self, content: &str, source: &str, directory: &Path) -> Result<(), Error> {
+        let statements = syntax::parse_file(content, source)
+            .map_err(|e| Error::PestParseError { message: e.message })?;
+
+        for statement in statements {
+            let (name, result) = match statement.kind {
+                StatementKind::Import(path) => {
+                    self.load_path(&directory.join(path))?;
+                    continue;
+                }
+                StatementKind::PrefixSet(set) => {
+                    (set.name.clone(), self.load_prefix_set(set, source))
+                }
+                StatementKind::Unit(unit) => (unit.name.clone(), self.load_unit(unit, source)),
             };
-            dimension = dimension.mul(another_dimension.pow(term.exponent));
+            result.map_err(|error| Error::DefinitionError {
+                file: source.into(),
+                line: statement.line,
+                column: statement.column,
+                name,
+                error: Box::new(error),
+            })?;
         }
 
-        match one.transformation {
-            Transformation::Identity => {
-                registry
-                    .register(Unit::new_base(one.name.as_str(), dimension))
-                    .unwrap();
-            }
-            Transformation::Linear { scale, offset } => {
-                registry
-                    .register(Unit::new_linear(
-                        one.name.as_str(),
-                        dimension,
-                        scale,
-                        offset.unwrap_or(0.0),
-                    ))
-                    .unwrap();
+        Ok(())
+    }
+
+    fn load_prefix_set(&mut self, syntax: PrefixSetSyntax, source: &str) -> Result<(), Error> {
+        let set = evaluate::prefix_set(syntax)?;
+        let prefix_set = PrefixSet::new(
+            set.name,
+            set.prefixes
+                .into_iter()
+                .map(|(name, symbol, factor)| Prefix::new(name, symbol, factor))
+                .collect(),
+        );
+        claim(&mut self.prefix_set_origins, prefix_set.name(), source)?;
+
+        if self.registry.prefix_set(prefix_set.name()).is_some() {
+            match self.policy {
+                ConflictPolicy::Error => {}
+                ConflictPolicy::Skip => return Ok(()),
+                ConflictPolicy::Override => {
+                    self.registry.remove_prefix_set(prefix_set.name());
+                }
             }
-            Transformation::Decibel { p0 } => {
-                registry
-                    .register(Unit::new(
-                        one.name.as_str(),
-                        dimension,
-                        UnitTransformation::Decibel(DecibelTransformation::new(p0)),
-                    ))
-                    .unwrap();
+        }
+
+        self.registry.register_prefix_set(prefix_set)
+    }
+
+    fn load_unit(&mut self, one: UnitSyntax, source: &str) -> Result<(), Error> {
+        let mut names = Vec::new();
+        for name in std::iter::once(&one.name)
+            .chain(one.symbol.iter())
+            .chain(one.aliases.iter())
+        {
+            if !names.contains(name) {
+                claim(&mut self.unit_origins, name, source)?;
+                names.push(name.clone());
             }
         }
 
-        if one.prefixes == Prefixes::Standard {
-            match one.transformation {
-                Transformation::Decibel { .. } => {
-                    return Err(Error::PestParseError {
-                        message: "Decibel transformation is not compatible with standard prefixes"
-                            .into(),
-                    });
-                }
-                Transformation::Linear { scale, offset } => {
-                    if offset.unwrap_or(0.0) != 0.0 {
-                        return Err(Error::PestParseError {
-                            message: "Linear transformation with offset is not compatible with standard prefixes".into()
-                        });
-                    }
-                    for (prefix, _, factor) in SI_PREFIXES.iter() {
-                        registry
-                            .register(Unit::new_linear(
-                                format!("{}{}", prefix, one.name).as_str(),
-                                dimension,
-                                scale * factor,
-                                0.0,
-                            ))
-                            .unwrap();
-                    }
+        let conflicts: Vec<String> = names
+            .into_iter()
+            .filter(|name| self.registry.contains_exact(name))
+            .collect();
+        if let Some(name) = conflicts.first() {
+            match self.policy {
+                ConflictPolicy::Error => {
+                    return Err(Error::RegistryAlreadyContainsUnit { name: name.clone() });
                 }
-                Transformation::Identity => {
-                    for (prefix, _, factor) in SI_PREFIXES.iter() {
-                        registry
-                            .register(Unit::new_linear(
-                                format!("{}{}", prefix, one.name).as_str(),
-                                dimension,
-                                *factor,
-                                0.0,
-                            ))
-                            .unwrap();
+                ConflictPolicy::Skip => return Ok(()),
+                ConflictPolicy::Override => {
+                    for name in &conflicts {
+                        match self.registry.remove(name) {
+                            Some(unit) => {
+                                if let Some(delta) = unit.delta() {
+                                    self.registry.remove(delta.name());
+                                }
+                            }
+                            None => {
+                                self.registry.remove_alias(name);
+                            }
+                        }
                     }
                 }
             }
         }
+
+        define_unit(self.registry, one)
     }
+}
 
-    Ok(registry)
+fn claim(origins: &mut HashMap<String, String>, name: &str, source: &str) -> Result<(), Error> {
+    if let Some(first) = origins.get(name) {
+        return Err(Error::DuplicateDefinition {
+            name: name.into(),
+            first: first.clone(),
+            second: source.into(),
+        });
+    }
+
+    origins.insert(name.into(), source.into());
+    Ok(())
 }
 
-fn parse_unit_definition(pair: Pair<Rule>) -> UnitDefinition {
-    let mut name = String::new();
-    let mut dimension = DimensionExpression { terms: Vec::new() };
-    let mut transformation = Transformation::Identity;
-    let mut prefixes = Prefixes::No;
-
-    for inner_pair in pair.into_inner() {
-        match inner_pair.as_rule() {
-            Rule::identifier => name = inner_pair.as_str().into(),
-            Rule::dimension_property => dimension = parse_dimension_property(inner_pair),
-            Rule::transformation_property => {
-                transformation = parse_transformation_property(inner_pair)
-            }
-            Rule::prefixes_property => prefixes = parse_prefixes_property(inner_pair),
-            _ => unreachable!(),
+fn define_unit(registry: &mut UnitRegistry, one: UnitSyntax) -> Result<(), Error> {
+    let unit = match &one.definition {
+        DefinitionSyntax::Base {
+            dimension,
+            transformation,
+        } => Unit::new(
+            one.name.as_str(),
+            Dimension::new(evaluate::dimension(dimension)?),
+            unit_transformation(evaluate::transformation(transformation)?),
+        ),
+        DefinitionSyntax::Derived { expression } => {
+            let unit = evaluate::derived_unit(&RegistryScope(registry), expression).map_err(
+                |e| match e {
+                    EvaluationError::UndefinedUnit(reference) => Error::UndefinedUnitReference {
+                        unit: one.name.clone(),
+                        reference,
+                    },
+                    e => e.into(),
+                },
+            )?;
+            Unit::new(
+                one.name.as_str(),
+                *unit.dimensionality(),
+                unit.transformation().clone(),
+            )
+        }
+    };
+    let unit = match &one.symbol {
+        Some(symbol) => unit.with_symbol(symbol.as_str()),
+        None => unit,
+    };
+    let delta = unit.delta();
+    registry.register(unit)?;
+
+    for alias in one.aliases.iter() {
+        registry.register_alias(alias, &one.name)?;
+    }
+
+    // Biased units get a delta unit for differences, unless one is already defined
+    if let Some(delta) = delta
+        && !registry.contains_exact(delta.name())
+    {
+        let name = delta.name().to_string();
+        registry.register(delta)?;
+        for alias in one.aliases.iter() {
+            registry.register_alias(&format!("delta_{}", alias), &name)?;
         }
     }
 
-    UnitDefinition {
-        name,
-        dimension,
-        transformation,
-        prefixes,
+    for prefix_set in one.prefixes.iter() {
+        registry.allow_prefixes(&one.name, prefix_set)?;
     }
+
+    Ok(())
+}
+
+/// Parses a unit expression against a registry.
+///
+/// Expressions combine unit names and numeric factors with `*`, `/`, `^` and parentheses,
+/// e.g. `kilogram * meter / second^2` or `(kilometer / hour)^-1`. A number directly
+/// followed by a unit scales it, e.g. `2.54 centimeter`.
+///
+/// # Parameters
+/// - `registry`: Registry used to resolve unit names.
+/// - `expression`: Expression to parse.
+///
+/// # Returns
+/// `Ok(Unit)` with the combined dimension and scale, or an error.
+///
+/// # Errors
+/// - Invalid syntax.
+/// - Unknown unit names.
+/// - Biased, logarithmic or custom units combined with other units, zero exponents.
+pub fn parse_unit_expression(registry: &UnitRegistry, expression: &str) -> Result<Unit, Error> {
+    Ok(evaluate::unit_expression(
+        &RegistryScope(registry),
+        expression,
+    )?)
 }
 
-fn parse_dimension_property(pair: Pair<Rule>) -> DimensionExpression {
-    let mut terms = Vec::new();
+/// Parses a quantity such as `9.81 m/s^2` against a registry.
+///
+/// The magnitude accepts any float format (`5`, `-1.5e3`, `.5`, `inf`) and may be separated
+/// from the unit expression by whitespace.
+///
+/// # Errors
+/// - `InvalidQuantity` if the magnitude is malformed or the unit is missing.
+/// - Unit expression errors (see [`parse_unit_expression`]).
+pub fn parse_quantity(registry: &UnitRegistry, input: &str) -> Result<Quantity<f64>, Error> {
+    let trimmed = input.trim();
+    let Some((magnitude, unit_expression)) = syntax::split_magnitude(trimmed) else {
+        return Err(Error::InvalidQuantity {
+            input: input.into(),
+            message: "expected a numeric magnitude".into(),
+        });
+    };
 
-    for inner_pair in pair.into_inner() {
-        if inner_pair.as_rule() == Rule::dimension_expression {
-            terms = parse_dimension_expression(inner_pair);
-        }
+    let value = magnitude
+        .parse::<f64>()
+        .map_err(|e| Error::InvalidQuantity {
+            input: input.into(),
+            message: format!("invalid magnitude {}: {}", magnitude, e),
+        })?;
+
+    let unit_expression = unit_expression.trim();
+    if unit_expression.is_empty() {
+        return Err(Error::InvalidQuantity {
+            input: input.into(),
+            message: "missing unit".into(),
+        });
     }
 
-    DimensionExpression { terms }
+    let unit = parse_unit_expression(registry, unit_expression)?;
+    Ok(Quantity::new(value, unit))
 }
 
-fn parse_dimension_expression(pair: Pair<Rule>) -> Vec<DimensionTerm> {
-    let mut terms = Vec::new();
-    let mut current_operator = String::from("*");
+/// Units of a registry, for [`evaluate`].
+struct RegistryScope<'a>(&'a UnitRegistry);
 
-    for inner_pair in pair.into_inner() {
-        match inner_pair.as_rule() {
-            Rule::dimension_term => {
-                let mut term = parse_dimension_term(inner_pair);
-                if current_operator == "/" {
-                    term.exponent = -term.exponent;
-                }
-                terms.push(term);
-            }
-            Rule::operator => {
-                current_operator = inner_pair.as_str().to_string();
-            }
-            _ => unreachable!(),
-        }
+impl Scope for RegistryScope<'_> {
+    type Unit = Unit;
+
+    fn unit(&self, name: &str) -> Option<Unit> {
+        self.0.get_prefixed(name).map(Cow::into_owned)
     }
 
-    terms
+    fn number(&self, text: &str, value: f64) -> Unit {
+        Unit::new_linear(text, COUNT, value, 0.0)
+    }
 }
 
-fn parse_dimension_term(pair: Pair<Rule>) -> DimensionTerm {
-    let mut fundamental = String::new();
-    let mut exponent = 1;
+impl UnitValue for Unit {
+    fn name(&self) -> &str {
+        Unit::name(self)
+    }
 
-    for inner_pair in pair.into_inner() {
-        match inner_pair.as_rule() {
-            Rule::fundamental => fundamental = inner_pair.as_str().to_string(),
-            Rule::exponent => exponent = inner_pair.as_str().parse::<i64>().unwrap_or(1),
-            _ => {}
-        }
+    fn powers(&self) -> Powers {
+        self.dimensionality().powers()
     }
 
-    DimensionTerm {
-        fundamental,
-        exponent,
+    fn transformation(&self) -> Option<TransformationEntry> {
+        transformation_entry(Unit::transformation(self))
     }
-}
 
-fn parse_transformation_property(pair: Pair<Rule>) -> Transformation {
-    for inner_pair in pair.into_inner() {
-        if inner_pair.as_rule() == Rule::transformation {
-            return parse_transformation(inner_pair);
-        }
+    fn mul(self, rhs: Self) -> Self {
+        self * rhs
     }
 
-    Transformation::Identity
+    fn div(self, rhs: Self) -> Self {
+        self / rhs
+    }
+
+    fn pow(self, power: i32) -> Self {
+        Unit::pow(&self, power as i64)
+    }
 }
 
-fn parse_transformation(pair: Pair<Rule>) -> Transformation {
-    for inner_pair in pair.into_inner() {
-        match inner_pair.as_rule() {
-            Rule::identity_transformation => return Transformation::Identity,
-            Rule::linear_transformation => {
-                let mut scale = 0.0;
-                let mut offset = None;
-                for lp in inner_pair.into_inner() {
-                    match lp.as_rule() {
-                        Rule::number => {
-                            if scale == 0.0 {
-                                scale = lp.as_str().parse::<f64>().unwrap_or(1.0);
-                            } else {
-                                offset = Some(lp.as_str().parse::<f64>().unwrap_or(0.0));
-                            }
-                        }
-                        _ => unreachable!(),
-                    }
-                }
-                return Transformation::Linear { scale, offset };
-            }
-            Rule::decibel_transformation => {
-                let mut p0 = 0.0;
-                for lp in inner_pair.into_inner() {
-                    if lp.as_rule() == Rule::number {
-                        p0 = lp.as_str().parse::<f64>().unwrap_or(1.0);
-                    }
-                }
-                return Transformation::Decibel { p0 };
+impl From<EvaluationError> for Error {
+    fn from(error: EvaluationError) -> Self {
+        match error {
+            EvaluationError::InvalidDefinition(message) => Error::InvalidDefinition { message },
+            EvaluationError::UndefinedUnit(name) => Error::RegistryDoesNotContainUnit { name },
+            EvaluationError::InvalidExpression {
+                expression,
+                message,
+            } => Error::InvalidUnitExpression {
+                expression,
+                message,
+            },
+            EvaluationError::PrefixCollision { set, prefix } => {
+                Error::PrefixCollision { name: set, prefix }
             }
-            _ => unreachable!(),
+            EvaluationError::Syntax(message) => Error::PestParseError { message },
         }
     }
+}
 
-    Transformation::Identity
+/// Plain data of a transformation, `None` for custom transformations.
+pub(crate) fn transformation_entry(
+    transformation: &UnitTransformation,
+) -> Option<TransformationEntry> {
+    Some(match transformation {
+        UnitTransformation::Identity => TransformationEntry::Identity,
+        UnitTransformation::Linear(linear) => TransformationEntry::Linear {
+            scale: linear.scale(),
+            offset: linear.offset(),
+        },
+        UnitTransformation::Logarithmic(logarithmic) => TransformationEntry::Logarithmic {
+            base: logarithmic.base(),
+            factor: logarithmic.factor(),
+            reference: logarithmic.reference(),
+            gain: logarithmic.is_gain(),
+        },
+        UnitTransformation::Custom(_) => return None,
+    })
 }
 
-fn parse_prefixes_property(pair: Pair<Rule>) -> Prefixes {
-    for inner_pair in pair.into_inner() {
-        if inner_pair.as_rule() == Rule::prefixes_expression {
-            match inner_pair.as_str() {
-                "standard" => return Prefixes::Standard,
-                "no" => return Prefixes::No,
-                _ => unreachable!(),
-            }
+fn unit_transformation(entry: TransformationEntry) -> UnitTransformation {
+    match entry {
+        TransformationEntry::Identity => UnitTransformation::Identity,
+        TransformationEntry::Linear { scale, offset } => {
+            UnitTransformation::Linear(LinearTransformation::new(scale, offset))
         }
+        TransformationEntry::Logarithmic {
+            base,
+            factor,
+            reference,
+            gain,
+        } => UnitTransformation::Logarithmic(LogarithmicTransformation {
+            base,
+            factor,
+            reference,
+            gain,
+        }),
     }
-
-    Prefixes::No
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     mod fixtures {
         use super::*;
@@ -342,6 +492,8 @@ mod tests {
                 dimension: length
                 transformation: identity
                 prefixes: standard
+                symbol: m
+                aliases: [metre, meters]
             }
             unit gram { 
                 dimension: mass
@@ -385,7 +537,11 @@ mod tests {
 
         let expected_names = vec!["meter", "kilogram", "decibel"];
         for name in &expected_names {
-            assert!(registry.get(name).is_some(), "Unit {} not found", name);
+            assert!(
+                registry.get_prefixed(name).is_some(),
+                "Unit {} not found",
+                name
+            );
         }
     }
 
@@ -407,7 +563,9 @@ mod tests {
     fn test_kilogram_dimensionality() {
         let registry = fixtures::parse_registry();
 
-        let kilogram = registry.get("kilogram").expect("Kilogram unit not found");
+        let kilogram = registry
+            .get_prefixed("kilogram")
+            .expect("Kilogram unit not found");
         assert_eq!(
             kilogram.name(),
             "kilogram",
@@ -427,7 +585,7 @@ mod tests {
 
         let registry = fixtures::parse_registry();
 
-        let kilogram = registry.get("kilogram").unwrap();
+        let kilogram = registry.get_prefixed("kilogram").unwrap();
         if let Linear(transformation) = kilogram.transformation() {
             assert_eq!(
                 transformation.scale, 1.0,
@@ -469,8 +627,11 @@ mod tests {
         let registry = fixtures::parse_registry();
 
         let decibel = registry.get("decibel").expect("Decibel unit not found");
-        if let Decibel(transformation) = decibel.transformation() {
-            assert_eq!(transformation.p0, 1.0, "Decibel p0 coefficient incorrect");
+        if let Logarithmic(transformation) = decibel.transformation() {
+            assert_eq!(
+                transformation.reference, 1.0,
+                "Decibel p0 coefficient incorrect"
+            );
         } else {
             unreachable!();
         }
@@ -480,5 +641,564 @@ mod tests {
             10.0,
             "Decibel transformation incorrect"
         );
+
+        let registry = parse_units_file(
+            "unit decibel_volt {
+                dimension: mass * length^2 / time^3 / current
+                transformation: decibel(p0: 1, factor: 20)
+                prefixes: no
+            }",
+        )
+        .unwrap();
+        let decibel_volt = registry.get("decibel_volt").unwrap();
+        assert_eq!(
+            decibel_volt.transformation(),
+            &Logarithmic(LogarithmicTransformation::decibel_root_power(1.0))
+        );
+        assert_eq!(decibel_volt.to_base(20.0), 10.0);
+    }
+
+    #[test]
+    fn test_logarithmic_transformation() {
+        use std::f64::consts::E;
+
+        let registry = parse_units_file(
+            "unit neper {
+                dimension: count
+                transformation: log(base: e, factor: 0.5, ref: 1, gain: true)
+                prefixes: no
+            }
+            unit octave { dimension: count transformation: log(base: 2) prefixes: no }
+            unit pH {
+                dimension: amount of substance / length^3
+                transformation: log(base: 10, factor: -1, ref: 1000)
+                prefixes: no
+            }",
+        )
+        .unwrap();
+
+        assert_eq!(
+            registry.get("neper").unwrap().transformation(),
+            &UnitTransformation::Logarithmic(LogarithmicTransformation::new_gain(E, 0.5))
+        );
+        assert_eq!(registry.get("octave").unwrap().to_base(3.0), 8.0);
+        assert!(!matches!(
+            registry.get("octave").unwrap().transformation(),
+            UnitTransformation::Logarithmic(octave) if octave.is_gain()
+        ));
+
+        let ph = registry.get("pH").unwrap();
+        assert_eq!(ph.dimensionality(), &(AMOUNT_OF_SUBSTANCE / LENGTH.pow(3)));
+        assert!((ph.from_base(1.0e-4) - 7.0).abs() < 1.0e-12);
+
+        assert!(matches!(
+            parse_units_file("unit x { dimension: count transformation: log(base: e) prefixes: standard }"),
+            Err(Error::DefinitionError { error, .. }) if matches!(*error, Error::IncompatiblePrefixes { .. })
+        ));
+    }
+
+    #[test]
+    fn test_unit_expression() {
+        let registry = fixtures::parse_registry();
+
+        let force = parse_unit_expression(&registry, "kilogram * meter / second^2");
+        assert_eq!(
+            force,
+            Err(Error::RegistryDoesNotContainUnit {
+                name: "second".into()
+            })
+        );
+
+        let force =
+            parse_unit_expression(&registry, "kilogram * meter / (millimeter * 1e3)^2").unwrap();
+        assert_eq!(force.dimensionality(), &(MASS / LENGTH));
+        assert!((force.to_base(1.0) - 1.0).abs() < 1.0e-9);
+
+        let area = parse_unit_expression(&registry, "2.5 meter^2").unwrap();
+        assert_eq!(area.dimensionality(), &LENGTH.pow(2));
+        assert_eq!(area.to_base(1.0), 2.5);
+
+        let celsius = parse_unit_expression(&registry, "degree_celsius").unwrap();
+        assert_eq!(celsius.to_base(0.0), 273.15);
+    }
+
+    #[test]
+    fn test_invalid_unit_expression() {
+        let registry = fixtures::parse_registry();
+
+        assert!(matches!(
+            parse_unit_expression(&registry, "meter * "),
+            Err(Error::PestParseError { .. })
+        ));
+        assert!(matches!(
+            parse_unit_expression(&registry, "meter^0"),
+            Err(Error::InvalidUnitExpression { .. })
+        ));
+        assert!(matches!(
+            parse_unit_expression(&registry, "degree_celsius / meter"),
+            Err(Error::InvalidUnitExpression { .. })
+        ));
+        assert!(matches!(
+            parse_unit_expression(&registry, "decibel^2"),
+            Err(Error::InvalidUnitExpression { .. })
+        ));
+        for expression in [
+            "meter^99999999999",
+            "meter^2147483647 * meter",
+            "(meter^-2147483647)^2",
+        ] {
+            assert!(
+                matches!(
+                    parse_unit_expression(&registry, expression),
+                    Err(Error::InvalidUnitExpression { .. })
+                ),
+                "{}",
+                expression
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_quantity() {
+        let registry = fixtures::parse_registry();
+
+        let weight = parse_quantity(&registry, "9.81 kilogram*meter/(millimeter*1e3)^2").unwrap();
+        assert_eq!(weight.dimensionality(), &(MASS / LENGTH));
+        assert!((weight.base_magnitude() - 9.81).abs() < 1.0e-9);
+
+        let distance = parse_quantity(&registry, "  -1.5E3kilometer ").unwrap();
+        assert_eq!(distance.base_magnitude(), -1.5e6);
+
+        let temperature = parse_quantity(&registry, ".5 degree_celsius").unwrap();
+        assert_eq!(temperature.base_magnitude(), 273.65);
+
+        let distance = parse_quantity(&registry, "5 nanometer").unwrap();
+        assert!((distance.base_magnitude() - 5.0e-9).abs() < 1.0e-21);
+        assert!(
+            parse_quantity(&registry, "NaN meter")
+                .unwrap()
+                .base_magnitude()
+                .is_nan()
+        );
+        assert_eq!(
+            parse_quantity(&registry, "-inf meter")
+                .unwrap()
+                .base_magnitude(),
+            f64::NEG_INFINITY
+        );
+    }
+
+    #[test]
+    fn test_invalid_quantity() {
+        let registry = fixtures::parse_registry();
+
+        assert!(matches!(
+            parse_quantity(&registry, "meter"),
+            Err(Error::InvalidQuantity { .. })
+        ));
+        for input in ["nanometer", "infinitesimal meter", "inf_meter"] {
+            assert!(
+                matches!(
+                    parse_quantity(&registry, input),
+                    Err(Error::InvalidQuantity { .. })
+                ),
+                "{} parsed with a nan or inf magnitude",
+                input
+            );
+        }
+        assert!(matches!(
+            parse_quantity(&registry, "5.0"),
+            Err(Error::InvalidQuantity { .. })
+        ));
+        assert_eq!(
+            parse_quantity(&registry, "5.0 parrot").unwrap_err(),
+            Error::RegistryDoesNotContainUnit {
+                name: "parrot".into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_symbols_and_aliases() {
+        let registry = fixtures::parse_registry();
+
+        let kilometer = registry.get_prefixed("kilometer").unwrap();
+        assert_eq!(kilometer.symbol(), Some("km"));
+        for name in ["km", "kilometre", "kilometers"] {
+            assert_eq!(
+                registry.get_prefixed(name),
+                Some(kilometer.clone()),
+                "{} not resolved",
+                name
+            );
+        }
+        assert_eq!(registry.get("metre").unwrap().name(), "meter");
+
+        let speed = parse_unit_expression(&registry, "km / m").unwrap();
+        assert_eq!(speed.to_base(1.0), 1.0e3);
+    }
+
+    #[test]
+    fn test_delta_units() -> Result<(), Error> {
+        let mut registry = parse_units_file(
+            "unit kelvin { dimension: temperature transformation: identity prefixes: no }
+            unit degree_celsius {
+                dimension: temperature
+                transformation: linear(scale: 1.0, offset: 273.15)
+                prefixes: no
+                symbol: °C
+                aliases: [celsius]
+            }",
+        )?;
+
+        let delta = registry.get("delta_degree_celsius").unwrap();
+        assert!(!delta.is_biased());
+        assert_eq!(delta.to_base(10.0), 10.0);
+        assert_eq!(registry.get("Δ°C"), Some(delta));
+        assert_eq!(registry.get("delta_celsius"), Some(delta));
+        assert!(registry.get("delta_kelvin").is_none());
+
+        let unit = parse_unit_expression(&registry, "kelvin / Δ°C")?;
+        assert_eq!(unit.dimensionality(), &COUNT);
+
+        registry.load_str(
+            "unit degree_celsius {
+                dimension: temperature
+                transformation: linear(scale: 2.0, offset: 273.15)
+                prefixes: no
+            }",
+            ConflictPolicy::Override,
+        )?;
+        assert_eq!(
+            registry.get("delta_degree_celsius").unwrap().to_base(1.0),
+            2.0
+        );
+        assert!(registry.get("Δ°C").is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_relative_definitions() {
+        let registry = parse_units_file(
+            r#"
+            unit meter {
+                dimension: length
+                transformation: identity
+                prefixes: standard
+            }
+            unit second {
+                dimension: time
+                transformation: identity
+                prefixes: no
+            }
+            unit inch = 2.54 centimeter
+            unit foot = 12 inch { symbol: ft }
+            unit hertz = 1 / second {
+                prefixes: standard
+                symbol: Hz
+            }
+            "#,
+        )
+        .unwrap();
+
+        let foot = registry.get("ft").unwrap();
+        assert_eq!(foot.name(), "foot");
+        assert_eq!(foot.dimensionality(), &LENGTH);
+        assert!((foot.to_base(1.0) - 0.3048).abs() < 1.0e-12);
+
+        let kilohertz = registry.get_prefixed("kHz").unwrap();
+        assert_eq!(kilohertz.dimensionality(), &TIME.pow(-1));
+        assert_eq!(kilohertz.to_base(1.0), 1.0e3);
+    }
+
+    #[test]
+    fn test_undefined_reference() {
+        let result = parse_units_file(
+            r#"
+            unit meter {
+                dimension: length
+                transformation: identity
+                prefixes: no
+            }
+            unit inch = 2.54 centimeter
+            "#,
+        );
+
+        assert_eq!(
+            result.err(),
+            Some(Error::DefinitionError {
+                file: "<string>".into(),
+                line: 7,
+                column: 13,
+                name: "inch".into(),
+                error: Box::new(Error::UndefinedUnitReference {
+                    unit: "inch".into(),
+                    reference: "centimeter".into()
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn test_custom_prefix_sets() {
+        let registry = parse_units_file(
+            r#"
+            prefix_set large {
+                kilo(k) = 1e3
+                mega(M) = 1e6
+            }
+            prefix_set russian {
+                pol = 0.5
+                chetvert = 0.25
+            }
+            unit dollar {
+                dimension: count
+                transformation: identity
+                prefixes: large
+                symbol: USD
+            }
+            unit arshin {
+                dimension: length
+                transformation: linear(scale: 0.7112)
+                prefixes: russian
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(registry.get_prefixed("MUSD").unwrap().name(), "megadollar");
+        assert_eq!(
+            registry.get_prefixed("kilodollar").unwrap().to_base(1.0),
+            1.0e3
+        );
+        assert!(registry.get_prefixed("millidollar").is_none());
+
+        let half_arshin = registry.get_prefixed("polarshin").unwrap();
+        assert!((half_arshin.to_base(1.0) - 0.3556).abs() < 1.0e-12);
+        assert_eq!(half_arshin.symbol(), None);
+    }
+
+    #[test]
+    fn test_unknown_prefix_set() {
+        let result = parse_units_file(
+            r#"
+            unit dollar {
+                dimension: count
+                transformation: identity
+                prefixes: large
+            }
+            "#,
+        );
+
+        assert_eq!(
+            result.err(),
+            Some(Error::DefinitionError {
+                file: "<string>".into(),
+                line: 2,
+                column: 13,
+                name: "dollar".into(),
+                error: Box::new(Error::UnknownPrefixSet {
+                    name: "large".into()
+                }),
+            })
+        );
+    }
+
+    fn definition_error(content: &str) -> (usize, String, Error) {
+        match parse_units_file(content).err() {
+            Some(Error::DefinitionError {
+                line, name, error, ..
+            }) => (line, name, *error),
+            other => panic!("Expected definition error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_definition_errors() {
+        let meter = "unit meter { dimension: length transformation: identity prefixes: no }\n";
+
+        assert_eq!(
+            definition_error(&format!("{}{}", meter, meter)),
+            (
+                2,
+                "meter".into(),
+                Error::DuplicateDefinition {
+                    name: "meter".into(),
+                    first: "<string>".into(),
+                    second: "<string>".into()
+                }
+            )
+        );
+
+        let invalid = [
+            "unit x { dimension: length transformation: linear(scale: 0, offset: 1) prefixes: no }",
+            "unit x { dimension: count transformation: decibel(p0: 0) prefixes: no }",
+            "unit x { dimension: count transformation: decibel(p0: -1) prefixes: no }",
+            "unit x { dimension: count transformation: decibel(p0: 1, factor: 0) prefixes: no }",
+            "unit x { dimension: count transformation: log(base: 1) prefixes: no }",
+            "unit x { dimension: count transformation: log(base: e, factor: 0) prefixes: no }",
+            "unit x { dimension: count transformation: log(base: 2, ref: -1) prefixes: no }",
+            "unit x { dimension: count transformation: log(base: 2, ref: 8, gain: true) prefixes: no }",
+            "unit x { dimension: count transformation: decibel(p0: 2, gain: true) prefixes: no }",
+            "unit x { dimension: length^0 transformation: identity prefixes: no }",
+            "unit x { dimension: length^99999999999 transformation: identity prefixes: no }",
+            "prefix_set x { half = 0.5 nothing = 0 }",
+        ];
+        for content in invalid {
+            assert!(
+                matches!(
+                    definition_error(content),
+                    (1, name, Error::InvalidDefinition { .. }) if name == "x"
+                ),
+                "{}",
+                content
+            );
+        }
+        assert!(matches!(
+            definition_error(&format!("{}unit x = 0 meter", meter)),
+            (2, _, Error::InvalidDefinition { .. })
+        ));
+
+        assert_eq!(
+            definition_error("prefix_set large { kilo(k) = 1e3 kilo(K) = 1e3 }"),
+            (
+                1,
+                "large".into(),
+                Error::PrefixCollision {
+                    name: "large".into(),
+                    prefix: "kilo".into()
+                }
+            )
+        );
+        assert_eq!(
+            definition_error(
+                "prefix_set large { thousand(k) = 1e3 }
+                 unit dollar { dimension: count transformation: identity prefixes: standard, large }"
+            ),
+            (
+                2,
+                "dollar".into(),
+                Error::PrefixCollision {
+                    name: "dollar".into(),
+                    prefix: "k".into()
+                }
+            )
+        );
+    }
+
+    /// Temporary directory of units files, unique per test run and removed when dropped.
+    struct UnitsDirectory(PathBuf);
+
+    impl UnitsDirectory {
+        fn new(files: &[(&str, &str)]) -> Self {
+            static COUNTER: AtomicUsize = AtomicUsize::new(0);
+            let directory = std::env::temp_dir().join(format!(
+                "arshin_{}_{}",
+                std::process::id(),
+                COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+            fs::create_dir_all(directory.join("company")).unwrap();
+            for (name, content) in files {
+                fs::write(directory.join(name), content).unwrap();
+            }
+
+            Self(directory)
+        }
+
+        fn join(&self, path: &str) -> PathBuf {
+            self.0.join(path)
+        }
+    }
+
+    impl Drop for UnitsDirectory {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_imports() {
+        let directory = UnitsDirectory::new(&[
+            (
+                "base.txt",
+                r#"
+                    unit meter {
+                        dimension: length
+                        transformation: identity
+                        prefixes: standard
+                        symbol: m
+                    }
+                    "#,
+            ),
+            (
+                "company/lengths.txt",
+                r#"
+                    import "../base.txt"
+                    unit arshin = 0.7112 meter
+                    "#,
+            ),
+            (
+                "company/units.txt",
+                r#"
+                    import "../base.txt"
+                    include "lengths.txt"
+                    unit sazhen = 3 arshin
+                    "#,
+            ),
+        ]);
+
+        let registry = parse_units_from_path(directory.join("company/units.txt")).unwrap();
+        assert!(registry.contains("km"));
+        assert!((registry.get("sazhen").unwrap().to_base(1.0) - 2.1336).abs() < 1.0e-12);
+    }
+
+    #[test]
+    fn test_import_errors() {
+        let directory = UnitsDirectory::new(&[
+            ("a.txt", r#"import "b.txt""#),
+            ("b.txt", r#"import "a.txt""#),
+            (
+                "base.txt",
+                "unit meter { dimension: length transformation: identity prefixes: no }",
+            ),
+            (
+                "company/units.txt",
+                r#"
+                    import "../base.txt"
+                    unit metre { dimension: length transformation: identity prefixes: no aliases: [meter] }
+                    "#,
+            ),
+        ]);
+
+        match parse_units_from_path(directory.join("a.txt")).err() {
+            Some(Error::ImportCycle { files }) => {
+                assert_eq!(files.len(), 3);
+                assert!(files[0].ends_with("a.txt") && files[2].ends_with("a.txt"));
+            }
+            other => panic!("Expected import cycle, got {:?}", other),
+        }
+
+        match parse_units_from_path(directory.join("company/units.txt")).err() {
+            Some(Error::DefinitionError { error, .. }) => {
+                let Error::DuplicateDefinition {
+                    name,
+                    first,
+                    second,
+                } = *error
+                else {
+                    panic!("Expected duplicate definition, got {:?}", error);
+                };
+                assert_eq!(name, "meter");
+                assert!(first.ends_with("base.txt"));
+                assert!(second.ends_with("units.txt"));
+            }
+            other => panic!("Expected duplicate definition, got {:?}", other),
+        }
+
+        assert!(matches!(
+            parse_units_file(r#"import "missing_units_file.txt""#),
+            Err(Error::OSError { .. })
+        ));
     }
 }
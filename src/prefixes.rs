@@ -0,0 +1,87 @@
+pub use arshin_dsl::prefixes::{BINARY_PREFIXES, SI_PREFIXES};
+
+/// A unit prefix, e.g. `kilo` (`k`, 1e3). An empty symbol means the prefix has no symbol.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Prefix {
+    pub name: String,
+    pub symbol: String,
+    pub factor: f64,
+}
+
+impl Prefix {
+    pub fn new(name: impl Into<String>, symbol: impl Into<String>, factor: f64) -> Self {
+        Self {
+            name: name.into(),
+            symbol: symbol.into(),
+            factor,
+        }
+    }
+}
+
+/// A named family of prefixes that units can opt into (e.g. `standard`, `binary`).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrefixSet {
+    pub name: String,
+    pub prefixes: Vec<Prefix>,
+}
+
+impl PrefixSet {
+    pub fn new(name: impl Into<String>, prefixes: Vec<Prefix>) -> Self {
+        Self {
+            name: name.into(),
+            prefixes,
+        }
+    }
+
+    /// SI prefixes (`kilo`, `mega`, `milli`, ...), named `standard`.
+    pub fn standard() -> Self {
+        Self::from_table("standard", &SI_PREFIXES)
+    }
+
+    /// IEC prefixes (`kibi`, `mebi`, `gibi`, ...), named `binary`.
+    pub fn binary() -> Self {
+        Self::from_table("binary", &BINARY_PREFIXES)
+    }
+
+    pub(crate) fn from_table(name: &str, table: &[(&str, &str, f64)]) -> Self {
+        Self::new(
+            name,
+            table
+                .iter()
+                .map(|(name, symbol, factor)| Prefix::new(*name, *symbol, *factor))
+                .collect(),
+        )
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn prefixes(&self) -> &[Prefix] {
+        &self.prefixes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_prefixes() {
+        let standard = PrefixSet::standard();
+        assert_eq!(standard.name(), "standard");
+        assert_eq!(standard.prefixes().len(), SI_PREFIXES.len());
+        assert!(standard.prefixes().contains(&Prefix::new("kilo", "k", 1e3)));
+    }
+
+    #[test]
+    fn test_binary_prefixes() {
+        let binary = PrefixSet::binary();
+        assert_eq!(binary.name(), "binary");
+        for (i, prefix) in binary.prefixes().iter().enumerate() {
+            assert_eq!(prefix.factor, 2.0f64.powi(10 * (i as i32 + 1)));
+        }
+    }
+}
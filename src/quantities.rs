@@ -1,10 +1,17 @@
 use crate::errors::ArshinError as Error;
+use crate::formatting::{FormatSpec, QuantityDisplay};
 use crate::fundamentals::Dimension;
-use crate::registry::DEFAULT_REGISTRY;
-use crate::registry::UnitRegistry;
-use crate::transformations::{LinearTransformation, MathOpsF64, UnitTransformation};
+use crate::fundamentals::base::COUNT;
+use crate::parser::parse_quantity;
+use crate::prefixes::Prefix;
+use crate::registry::{DEFAULT_REGISTRY, UnitRegistry};
+use crate::systems::UnitSystem;
+use crate::transformations::{LogarithmicTransformation, MathOpsF64, UnitTransformation};
 use crate::units::Unit;
+use std::borrow::Cow;
+use std::fmt;
 use std::ops::{Add, Div, Mul, Sub};
+use std::str::FromStr;
 
 /// Represents a physical quantity: magnitude in base units + unit.
 ///
@@ -12,7 +19,7 @@ use std::ops::{Add, Div, Mul, Sub};
 #[derive(Debug, Clone)]
 pub struct Quantity<T>
 where
-    T: MathOpsF64 + 'static,
+    T: MathOpsF64,
 {
     magnitude: T,
     unit: Unit,
@@ -20,14 +27,14 @@ where
 
 impl<T> Quantity<T>
 where
-    T: MathOpsF64 + 'static,
+    T: MathOpsF64,
 {
     /// Creates a quantity from magnitude and unit (converts to base internally).
     pub fn new(magnitude: T, unit: Unit) -> Self {
         let base_magnitude = unit.to_base(magnitude);
         Self {
             magnitude: base_magnitude,
-            unit: unit,
+            unit,
         }
     }
 
@@ -41,15 +48,16 @@ where
         unit_name: &str,
     ) -> Result<Self, Error> {
         let unit = registry
-            .get(unit_name)
+            .get_prefixed(unit_name)
             .ok_or(Error::RegistryDoesNotContainUnit {
                 name: unit_name.into(),
-            })?;
+            })?
+            .into_owned();
 
         let base_magnitude = unit.to_base(magnitude);
         Ok(Self {
             magnitude: base_magnitude,
-            unit: unit.clone(),
+            unit,
         })
     }
 
@@ -85,19 +93,456 @@ where
         self.magnitude
     }
 
+    /// Expresses the quantity in its reduced unit, with repeated factors cancelled and powers
+    /// merged (see [`Unit::reduced`]), e.g. `(meter / second) * second` → `meter`.
+    pub fn to_reduced_units(&self) -> Self {
+        Self {
+            magnitude: self.magnitude,
+            unit: self.unit.reduced(),
+        }
+    }
+
+    /// Expresses the quantity in its reduced unit, replaced with the matching named unit of the
+    /// registry if any (see [`Unit::simplify`]), e.g. `kilogram * meter / second^2` → `newton`.
+    pub fn simplify(&self, registry: &UnitRegistry) -> Self {
+        Self {
+            magnitude: self.magnitude,
+            unit: self.unit.simplify(registry),
+        }
+    }
+
+    /// Expresses the quantity in the coherent unit of a unit system (e.g. a force in CGS in
+    /// `((gram * centimeter) / [second]^2)`; see [`UnitSystem::coherent_unit`]).
+    ///
+    /// # Errors
+    /// `MissingBaseUnit` if the system has no base unit for a fundamental of the quantity.
+    pub fn to_system(&self, system: &UnitSystem) -> Result<Self, Error> {
+        Ok(Self {
+            magnitude: self.magnitude,
+            unit: system.coherent_unit(*self.dimensionality())?,
+        })
+    }
+
     /// Raises the quantity to a power (updates dimension and magnitude).
+    ///
+    /// # Panics
+    /// On biased or logarithmic units and zero power; see [`Quantity::checked_pow`].
     pub fn pow(&self, power: i64) -> Self {
-        match self.unit().transformation() {
-            UnitTransformation::Decibel(_) => panic!("Cannot raise a decibel quantity to a power"),
-            UnitTransformation::Linear(LinearTransformation { scale: _, offset }) => {
-                if *offset != 0.0 {
-                    panic!("Cannot raise a biased quantity to a power");
-                }
+        self.checked_pow(power)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Raises the quantity to a power (updates dimension and magnitude).
+    ///
+    /// # Errors
+    /// `BiasedUnitOperation`, `LogarithmicUnitOperation` or `CustomUnitOperation` if the unit has
+    /// an offset, logarithmic or custom transformation, `ZeroPower` if `power` is zero.
+    pub fn checked_pow(&self, power: i64) -> Result<Self, Error> {
+        Ok(Self {
+            magnitude: self.magnitude.pow(power as f64),
+            unit: self.unit.checked_pow(power)?,
+        })
+    }
+
+    /// Adds two quantities of the same dimensionality (the result keeps the unit of `self`).
+    ///
+    /// Biased units (e.g. `degree_celsius`) are absolute values: an absolute plus a difference
+    /// (a delta or unbiased unit, e.g. `delta_degree_celsius` or `kelvin`) is absolute, in the
+    /// unit of the absolute operand.
+    ///
+    /// Logarithmic quantities add logarithmically: two levels (e.g. dBm) sum their powers, and a
+    /// dimensionless gain (dB, bel or Np) shifts a level (e.g. 10 dBm + 3 dB = 13 dBm). Other
+    /// dimensionless logarithmic units (octaves, decades, magnitudes) add to each other only.
+    ///
+    /// Units with a custom transformation (e.g. wire gauge) add their base magnitudes.
+    ///
+    /// # Errors
+    /// - `UnitsConversionError` if dimensions differ.
+    /// - `BiasedUnitOperation` when adding two absolute (biased) quantities.
+    /// - `LogarithmicUnitOperation` when adding a logarithmic and a linear quantity, or an octave,
+    ///   decade or magnitude to a level or gain.
+    pub fn checked_add(&self, other: &Self) -> Result<Self, Error>
+    where
+        T: Add<Output = T>,
+    {
+        match (Logarithmic::of(&self.unit), Logarithmic::of(&other.unit)) {
+            (None, None) => {}
+            (Some(Logarithmic::Level(level)), Some(Logarithmic::Level(_))) => {
+                self.check_compatible(other)?;
+                // Power sum, in powers relative to the reference
+                let exponent = level.power_exponent() / level.factor();
+                let power = |value: T| (value * exponent).exp(level.base());
+                let sum = (power(self.value()) + power(self.unit.from_base(other.magnitude)))
+                    .log(level.base())
+                    / exponent;
+                return Ok(Self::new(sum, self.unit.clone()));
+            }
+            (Some(Logarithmic::Level(level)), Some(Logarithmic::Gain)) => {
+                return Ok(Self::new(
+                    self.value() + other.gain_in(&level),
+                    self.unit.clone(),
+                ));
+            }
+            (Some(Logarithmic::Gain), Some(Logarithmic::Level(level))) => {
+                return Ok(Self::new(
+                    other.value() + self.gain_in(&level),
+                    other.unit.clone(),
+                ));
+            }
+            (Some(Logarithmic::Gain), Some(Logarithmic::Gain))
+            | (Some(Logarithmic::Ratio), Some(Logarithmic::Ratio)) => {
+                return Ok(Self::new(
+                    self.value() + other.value_in(&self.unit),
+                    self.unit.clone(),
+                ));
             }
-            _ => {}
+            (Some(_), Some(_)) => {
+                return Err(self.mixed_ratio(other, "addition"));
+            }
+            (Some(_), None) | (None, Some(_)) => {
+                return Err(self.mixed_logarithmic(other, "addition"));
+            }
+        }
+
+        self.check_compatible(other)?;
+        let unit = match (self.unit.is_biased(), other.unit.is_biased()) {
+            (true, true) => {
+                return Err(Error::BiasedUnitOperation {
+                    name: other.unit.name().into(),
+                    operation: "addition".into(),
+                });
+            }
+            (false, true) => other.unit.clone(),
+            _ => self.unit.clone(),
+        };
+
+        Ok(Self {
+            magnitude: self.magnitude + other.magnitude,
+            unit,
+        })
+    }
+
+    /// Subtracts two quantities of the same dimensionality (the result keeps the unit of `self`).
+    ///
+    /// The difference of two absolute (biased) quantities is a delta, e.g. 20 °C − 10 °C is
+    /// 10 `delta_degree_celsius`; an absolute minus a difference stays absolute.
+    ///
+    /// The difference of two logarithmic levels is a gain in dB (e.g. 13 dBm − 10 dBm = 3 dB),
+    /// and subtracting a gain from a level lowers it.
+    ///
+    /// # Errors
+    /// - `UnitsConversionError` if dimensions differ.
+    /// - `BiasedUnitOperation` when subtracting an absolute (biased) quantity from a difference.
+    /// - `LogarithmicUnitOperation` when subtracting a level from a gain, or mixing logarithmic
+    ///   and linear quantities, or octaves, decades or magnitudes with levels or gains.
+    pub fn checked_sub(&self, other: &Self) -> Result<Self, Error>
+    where
+        T: Sub<Output = T>,
+    {
+        match (Logarithmic::of(&self.unit), Logarithmic::of(&other.unit)) {
+            (None, None) => {}
+            (Some(Logarithmic::Level(level)), Some(Logarithmic::Level(_))) => {
+                self.check_compatible(other)?;
+                // Power ratio of the two levels
+                let difference = self.value() - self.unit.from_base(other.magnitude);
+                let ratio =
+                    (difference * (level.power_exponent() / level.factor())).exp(level.base());
+                return Ok(Self {
+                    magnitude: ratio,
+                    unit: Logarithmic::gain_unit(&level),
+                });
+            }
+            (Some(Logarithmic::Level(level)), Some(Logarithmic::Gain)) => {
+                return Ok(Self::new(
+                    self.value() - other.gain_in(&level),
+                    self.unit.clone(),
+                ));
+            }
+            (Some(Logarithmic::Gain), Some(Logarithmic::Gain))
+            | (Some(Logarithmic::Ratio), Some(Logarithmic::Ratio)) => {
+                return Ok(Self::new(
+                    self.value() - other.value_in(&self.unit),
+                    self.unit.clone(),
+                ));
+            }
+            (Some(Logarithmic::Gain), Some(Logarithmic::Level(_))) => {
+                return Err(Error::LogarithmicUnitOperation {
+                    name: other.unit.name().into(),
+                    operation: "subtraction from a gain".into(),
+                });
+            }
+            (Some(_), Some(_)) => {
+                return Err(self.mixed_ratio(other, "subtraction"));
+            }
+            (Some(_), None) | (None, Some(_)) => {
+                return Err(self.mixed_logarithmic(other, "subtraction"));
+            }
+        }
+
+        self.check_compatible(other)?;
+        let unit = match (self.unit.delta(), other.unit.is_biased()) {
+            (Some(delta), true) => delta,
+            (None, true) => {
+                return Err(Error::BiasedUnitOperation {
+                    name: other.unit.name().into(),
+                    operation: "subtraction".into(),
+                });
+            }
+            _ => self.unit.clone(),
+        };
+
+        Ok(Self {
+            magnitude: self.magnitude - other.magnitude,
+            unit,
+        })
+    }
+
+    /// Multiplies two quantities.
+    ///
+    /// # Errors
+    /// `BiasedUnitOperation`, `LogarithmicUnitOperation` or `CustomUnitOperation` if either unit
+    /// has an offset, logarithmic or custom transformation.
+    pub fn checked_mul(&self, other: &Self) -> Result<Self, Error>
+    where
+        T: Mul<Output = T>,
+    {
+        Ok(Self {
+            magnitude: self.magnitude * other.magnitude,
+            unit: self.unit.checked_mul(&other.unit)?,
+        })
+    }
+
+    /// Divides two quantities.
+    ///
+    /// # Errors
+    /// `BiasedUnitOperation`, `LogarithmicUnitOperation` or `CustomUnitOperation` if either unit
+    /// has an offset, logarithmic or custom transformation.
+    pub fn checked_div(&self, other: &Self) -> Result<Self, Error>
+    where
+        T: Div<Output = T>,
+    {
+        Ok(Self {
+            magnitude: self.magnitude / other.magnitude,
+            unit: self.unit.checked_div(&other.unit)?,
+        })
+    }
+
+    /// Magnitude in the quantity's own unit.
+    fn value(&self) -> T {
+        self.unit.from_base(self.magnitude)
+    }
+
+    /// Magnitude in `unit`, of the same dimensionality; the value itself in the quantity's own
+    /// unit, so that values of the same unit add without a round trip through the base unit.
+    fn value_in(&self, unit: &Unit) -> T {
+        if unit == &self.unit {
+            self.value()
+        } else {
+            unit.from_base(self.magnitude)
+        }
+    }
+
+    /// Gain (a power ratio) as a shift of levels in the `level` scale.
+    fn gain_in(&self, level: &LogarithmicTransformation) -> T {
+        self.magnitude.log(level.base()) * (level.factor() / level.power_exponent())
+    }
+
+    /// Error for an operation mixing a logarithmic and a linear quantity.
+    fn mixed_logarithmic(&self, other: &Self, operation: &str) -> Error {
+        let unit = match Logarithmic::of(&self.unit) {
+            Some(_) => &self.unit,
+            None => &other.unit,
+        };
+        Error::LogarithmicUnitOperation {
+            name: unit.name().into(),
+            operation: format!("{} with a linear quantity", operation),
         }
+    }
+
+    /// Error for an operation mixing a logarithmic ratio (e.g. octaves) with a level or a gain.
+    fn mixed_ratio(&self, other: &Self, operation: &str) -> Error {
+        let unit = match Logarithmic::of(&self.unit) {
+            Some(Logarithmic::Ratio) => &self.unit,
+            _ => &other.unit,
+        };
+        Error::LogarithmicUnitOperation {
+            name: unit.name().into(),
+            operation: format!("{} with a level or gain", operation),
+        }
+    }
+
+    fn check_compatible(&self, other: &Self) -> Result<(), Error> {
+        if self.dimensionality() != other.dimensionality() {
+            Err(Error::UnitsConversionError {
+                expected: *self.dimensionality(),
+                got: *other.dimensionality(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Role of a logarithmic unit in additions and subtractions.
+enum Logarithmic {
+    /// Level relative to a dimensioned reference, e.g. dBm (1 mW) or dBV (1 V).
+    Level(LogarithmicTransformation),
+    /// Dimensionless power ratio, e.g. a gain or attenuation in dB or Np (see
+    /// [`LogarithmicTransformation::new_gain`]).
+    Gain,
+    /// Other dimensionless ratio, e.g. octaves, decades or astronomical magnitudes; it adds to
+    /// ratios only, not to levels or gains.
+    Ratio,
+}
+
+impl Logarithmic {
+    fn of(unit: &Unit) -> Option<Self> {
+        let UnitTransformation::Logarithmic(transformation) = unit.transformation() else {
+            return None;
+        };
+
+        Some(if unit.dimensionality() != &COUNT {
+            Self::Level(*transformation)
+        } else if transformation.is_gain() {
+            Self::Gain
+        } else {
+            Self::Ratio
+        })
+    }
+
+    /// Unit of differences of `level`s: the gain unit on the level's own scale (e.g. nepers for
+    /// a level in nepers), taken from the default units if they define one.
+    fn gain_unit(level: &LogarithmicTransformation) -> Unit {
+        let gain = LogarithmicTransformation::new_gain(
+            level.base(),
+            level.factor() / level.power_exponent(),
+        );
+        let transformation = UnitTransformation::Logarithmic(gain);
+
+        DEFAULT_REGISTRY
+            .units()
+            .find(|unit| {
+                unit.dimensionality() == &COUNT && *unit.transformation() == transformation
+            })
+            .cloned()
+            .unwrap_or_else(|| {
+                let name = format!("gain(base: {}, factor: {})", gain.base(), gain.factor());
+                Unit::new(name, COUNT, transformation)
+            })
+    }
+}
+
+impl Quantity<f64> {
+    /// Expresses the quantity in the prefixed unit of its family that brings the magnitude into
+    /// [1, 1000) (e.g. 0.000012 second → 12 microsecond, 3.2e9 byte → 3.2 Gigabyte).
+    ///
+    /// Only the prefixes the unit accepts in `registry` are used, and of those only the ones in
+    /// steps of 1000 (`kilo`, `Mega`, `milli`, ..., not `centi` or `kibi`). Derived units and
+    /// units without prefixes are kept; zero and non-finite magnitudes use the unprefixed unit.
+    /// So are units that `registry` defines differently under the same name (e.g. a `bar` of
+    /// length in a registry where `bar` is a pressure).
+    pub fn to_best_prefix(&self, registry: &UnitRegistry) -> Self {
+        let Some((unit, prefix)) = registry.unprefixed(self.unit.name()) else {
+            return self.clone();
+        };
+        let prefixed = match prefix {
+            Some(prefix) => Cow::Owned(unit.with_prefix(prefix)),
+            None => Cow::Borrowed(unit),
+        };
+        if prefixed.dimensionality() != self.unit.dimensionality()
+            || prefixed.transformation() != self.unit.transformation()
+        {
+            return self.clone();
+        }
+
+        let value = unit.from_base(self.magnitude).abs();
+        let is_engineering = |prefix: &&Prefix| {
+            let exponent = prefix.factor.log10() / 3.0;
+            (exponent - exponent.round()).abs() < 1.0e-9
+        };
+        let factor = |prefix: &Option<&Prefix>| prefix.map_or(1.0, |prefix| prefix.factor);
+
+        let mut candidates: Vec<Option<&Prefix>> = std::iter::once(None)
+            .chain(
+                registry
+                    .accepted_prefixes(unit.name())
+                    .filter(is_engineering)
+                    .map(Some),
+            )
+            .collect();
+        candidates.sort_by(|a, b| factor(a).total_cmp(&factor(b)));
+
+        // The largest prefix not above the value, or the smallest one for tiny values
+        let prefix = if value == 0.0 || !value.is_finite() {
+            None
+        } else {
+            candidates
+                .iter()
+                .rev()
+                .find(|prefix| factor(prefix) <= value * (1.0 + 1.0e-12))
+                .or(candidates.first())
+                .copied()
+                .flatten()
+        };
+
+        Self {
+            magnitude: self.magnitude,
+            unit: match prefix {
+                Some(prefix) => unit.with_prefix(prefix),
+                None => unit.clone(),
+            },
+        }
+    }
+
+    /// Expresses the quantity with the best prefix of the default registry; see
+    /// [`Quantity::to_best_prefix`].
+    pub fn to_compact(&self) -> Self {
+        self.to_best_prefix(&DEFAULT_REGISTRY)
+    }
+
+    /// Formats the quantity with a style (plain, Unicode, LaTeX, HTML), long names or symbols,
+    /// and optionally the best prefix; the precision and `{:e}` of the format string apply to the
+    /// magnitude.
+    ///
+    /// # Examples
+    /// `format!("{:.1}", speed.display(FormatSpec::new(FormatStyle::Unicode)))` gives `9.8 m·s⁻²`.
+    pub fn display(&self, spec: FormatSpec) -> QuantityDisplay<'_> {
+        QuantityDisplay {
+            quantity: self,
+            spec,
+        }
+    }
+}
+
+/// Formats the magnitude in the quantity's unit and the unit symbol in plain style
+/// (`9.81 m/s^2`); honours precision (`{:.3}`), and the alternate flag (`{:#}`) rescales to the
+/// best prefix first (`12 µs`). See [`Quantity::display`] for other styles.
+impl fmt::Display for Quantity<f64> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.display(plain_spec(f)), f)
+    }
+}
+
+/// Formats the magnitude in scientific notation (`{:e}`, `{:.2e}`); see [`fmt::Display`].
+impl fmt::LowerExp for Quantity<f64> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerExp::fmt(&self.display(plain_spec(f)), f)
+    }
+}
 
-        Self::new(self.magnitude.pow(power as f64), self.unit().pow(power))
+fn plain_spec(f: &fmt::Formatter<'_>) -> FormatSpec {
+    FormatSpec {
+        compact: f.alternate(),
+        ..FormatSpec::default()
+    }
+}
+
+/// Parses a quantity such as `9.81 meter / second^2` using the default registry.
+impl FromStr for Quantity<f64> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_quantity(&DEFAULT_REGISTRY, s)
     }
 }
 
@@ -131,7 +576,8 @@ where
     }
 }
 
-// Addition of two quantities
+// Addition of two quantities; panics on different dimensionalities, two absolute temperatures
+// or mixed logarithmic and linear quantities (see `Quantity::checked_add`)
 impl<T> Add<Quantity<T>> for Quantity<T>
 where
     T: MathOpsF64 + Add<Output = T>,
@@ -139,21 +585,14 @@ where
     type Output = Self;
 
     fn add(self, other: Quantity<T>) -> Self::Output {
-        if self.dimensionality() != other.dimensionality() {
-            let error = Error::UnitsConversionError {
-                expected: *self.dimensionality(),
-                got: *other.dimensionality(),
-            };
-            panic!("{}", error);
-        }
-        Self {
-            magnitude: self.magnitude + other.magnitude,
-            unit: self.unit,
-        }
+        self.checked_add(&other)
+            .unwrap_or_else(|error| panic!("{}", error))
     }
 }
 
-// Subtraction of two quantities
+// Subtraction of two quantities; panics on different dimensionalities, an absolute temperature
+// subtracted from a difference or mixed logarithmic and linear quantities (see
+// `Quantity::checked_sub`)
 impl<T> Sub<Quantity<T>> for Quantity<T>
 where
     T: MathOpsF64 + Sub<Output = T>,
@@ -161,70 +600,61 @@ where
     type Output = Self;
 
     fn sub(self, other: Quantity<T>) -> Self::Output {
-        if self.dimensionality() != other.dimensionality() {
-            let error = Error::UnitsConversionError {
-                expected: *self.dimensionality(),
-                got: *other.dimensionality(),
-            };
-            panic!("{}", error);
-        }
-        Self {
-            magnitude: self.magnitude - other.magnitude,
-            unit: self.unit,
-        }
+        self.checked_sub(&other)
+            .unwrap_or_else(|error| panic!("{}", error))
     }
 }
 
-// Multiplication of two quantities
+// Multiplication of two quantities; panics on biased or logarithmic units (see `Quantity::checked_mul`)
 impl<T> Mul<Quantity<T>> for Quantity<T>
 where
-    T: MathOpsF64 + Mul<Output = T> + 'static,
+    T: MathOpsF64 + Mul<Output = T>,
 {
     type Output = Self;
 
     fn mul(self, other: Quantity<T>) -> Self::Output {
-        let new_unit = self.unit.clone() * other.unit.clone();
-        let new_magnitude = self.magnitude * other.magnitude;
-        Self {
-            magnitude: new_magnitude,
-            unit: new_unit,
-        }
+        self.checked_mul(&other)
+            .unwrap_or_else(|error| panic!("{}", error))
     }
 }
 
-// Division of two quantities
+// Division of two quantities; panics on biased or logarithmic units (see `Quantity::checked_div`)
 impl<T> Div<Quantity<T>> for Quantity<T>
 where
-    T: MathOpsF64 + Div<Output = T> + 'static,
+    T: MathOpsF64 + Div<Output = T>,
 {
     type Output = Self;
 
     fn div(self, other: Quantity<T>) -> Self::Output {
-        let new_unit = self.unit.clone() / other.unit.clone();
-        let new_magnitude = self.magnitude / other.magnitude;
-        Self {
-            magnitude: new_magnitude,
-            unit: new_unit,
-        }
+        self.checked_div(&other)
+            .unwrap_or_else(|error| panic!("{}", error))
     }
 }
 
 /// Macro to create a quantity from value and unit name (using custom or default registry).
+///
+/// The registry can be any expression evaluating to a `UnitRegistry` or a reference to one.
+/// Returns `Result<Quantity<T>, ArshinError>`.
+///
+/// # Examples
+/// `q!(5.0, "meter")`, `q!(registry, 5.0, "meter")` or `q!(ctx.units(), 5.0, name)`.
 #[macro_export]
 macro_rules! q {
-    ($registry:ident, $value:expr, $unit_name:expr) => {
-        Quantity::new_from_registry(&$registry, $value, $unit_name)
+    ($registry:expr, $value:expr, $unit_name:expr) => {
+        $crate::quantities::Quantity::new_from_registry(&$registry, $value, &$unit_name)
     };
 
     ($value:expr, $unit_name:expr) => {
-        Quantity::new_from_registry(&DEFAULT_REGISTRY, $value, $unit_name)
+        $crate::q!(*$crate::registry::DEFAULT_REGISTRY, $value, $unit_name)
     };
 }
 
 #[cfg(test)]
 mod tests {
     use crate::fundamentals::base::*;
-    use crate::registry::UnitRegistry;
+    use crate::parser::parse_units_file;
+    use crate::registry::ConflictPolicy;
+    use crate::transformations::UnitConversion;
     use crate::u;
 
     use super::*;
@@ -254,14 +684,14 @@ mod tests {
         let r = UnitRegistry::new_from_file("src/units.txt")?;
 
         let meter = r.get("meter").unwrap();
-        let kilometer = r.get("kilometer").unwrap();
+        let kilometer = r.get_prefixed("kilometer").unwrap();
         let foot = r.get("foot").unwrap();
 
         let meters = Quantity::new_from_registry(&r, 5000.0, "meter")?;
         let kilometers = Quantity::new_from_registry(&r, 5.0, "kilometer")?;
 
         assert_eq!(kilometers.magnitude_as(meter).unwrap(), 5000.0);
-        assert_eq!(meters.magnitude_as(kilometer).unwrap(), 5.0);
+        assert_eq!(meters.magnitude_as(&kilometer).unwrap(), 5.0);
 
         assert_eq!(kilometers.magnitude_as(foot).unwrap().round(), 16404.0);
         assert_eq!(meters.magnitude_as(foot).unwrap().round(), 16404.0);
@@ -355,4 +785,388 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_quantity_from_str() -> Result<(), Error> {
+        let acceleration: Quantity<f64> = "9.81 meter/second^2".parse()?;
+        assert_eq!(acceleration.m_as(&u!("meter_per_second_squared")?)?, 9.81);
+
+        let distance = Quantity::from_str("1.5e3kilometer")?;
+        assert_eq!(distance.m_as(&u!("meter")?)?, 1.5e6);
+
+        assert!(Quantity::from_str("five meter").is_err());
+
+        let wavelength: Quantity<f64> = "532 nanometer".parse()?;
+        assert!((wavelength.m_as(&u!("meter")?)? - 5.32e-7).abs() < 1.0e-18);
+        assert!(matches!(
+            "nanometer".parse::<Quantity<f64>>(),
+            Err(Error::InvalidQuantity { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "degree_celsius")]
+    fn test_add_absolute_temperatures() {
+        let _ = q!(20.0, "degree_celsius").unwrap() + q!(10.0, "degree_celsius").unwrap();
+    }
+
+    #[test]
+    fn test_temperature_differences() -> Result<(), Error> {
+        let kelvin = u!("kelvin")?;
+        let warm = q!(20.0, "degree_celsius")?;
+        let cold = q!(10.0, "°C")?;
+
+        let difference = warm.checked_sub(&cold)?;
+        assert_eq!(difference.unit().name(), "delta_degree_celsius");
+        assert!((difference.m_as(&kelvin)? - 10.0).abs() < 1.0e-9);
+
+        let warmer = warm.checked_add(&q!(5.0, "delta_degree_celsius")?)?;
+        assert_eq!(warmer.unit().name(), "degree_celsius");
+        assert!((warmer.m_as(&u!("degree_celsius")?)? - 25.0).abs() < 1.0e-9);
+
+        let warmer = q!(9.0, "Δ°F")?.checked_add(&warm)?;
+        assert_eq!(warmer.unit().name(), "degree_celsius");
+        assert!((warmer.m_as(&u!("degree_celsius")?)? - 25.0).abs() < 1.0e-6);
+
+        let cooler = warm.checked_sub(&q!(5.0, "kelvin")?)?;
+        assert!((cooler.m_as(&u!("degree_celsius")?)? - 15.0).abs() < 1.0e-9);
+
+        assert_eq!(
+            warm.checked_add(&cold).err(),
+            Some(Error::BiasedUnitOperation {
+                name: "degree_celsius".into(),
+                operation: "addition".into(),
+            })
+        );
+        assert!(matches!(
+            difference.checked_sub(&warm),
+            Err(Error::BiasedUnitOperation { .. })
+        ));
+
+        assert_eq!((warm.clone() - cold).unit().name(), "delta_degree_celsius");
+
+        let heat_capacity =
+            DEFAULT_REGISTRY.parse_unit("joule / (kilogram * delta_degree_celsius)")?;
+        let energy = Quantity::new(4186.0, heat_capacity) * q!(2.0, "kilogram")? * difference;
+        assert!((energy.m_as(&u!("joule")?)? - 83720.0).abs() < 1.0e-6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decibel_arithmetic() -> Result<(), Error> {
+        let close = |a: f64, b: f64| (a - b).abs() < 1.0e-9;
+        let dbm = u!("dBm")?;
+
+        // Two equal powers sum to +3.01 dB
+        let total = q!(10.0, "dBm")?.checked_add(&q!(10.0, "dBm")?)?;
+        assert!(close(total.m_as(&dbm)?, 10.0 + 10.0 * 2.0f64.log10()));
+        assert!(close(total.m_as(&u!("milliwatt")?)?, 20.0));
+
+        // Levels in different references: 0 dBW = 30 dBm
+        let total = q!(30.0, "dBm")?.checked_add(&q!(0.0, "dBW")?)?;
+        assert!(close(total.m_as(&u!("watt")?)?, 2.0));
+
+        // Gains shift levels, in both orders
+        let level = q!(10.0, "dBm")?.checked_add(&q!(3.0, "dB")?)?;
+        assert!(close(level.m_as(&dbm)?, 13.0));
+        let level = q!(-2.0, "dB")?.checked_add(&level)?;
+        assert_eq!(level.unit().name(), "decibel_milliwatt");
+        assert!(close(level.m_as(&dbm)?, 11.0));
+        let level = level.checked_sub(&q!(1.0, "dB")?)?;
+        assert!(close(level.m_as(&dbm)?, 10.0));
+
+        // Gains add in dB, level differences are gains
+        let gain = q!(3.0, "dB")?.checked_add(&q!(4.0, "dB")?)?;
+        assert!(close(gain.m_as(&u!("dB")?)?, 7.0));
+        let gain = q!(13.0, "dBm")?.checked_sub(&q!(-20.0, "dBW")?)?;
+        assert_eq!(gain.unit().name(), "decibel");
+        assert!(close(gain.m_as(&u!("dB")?)?, 3.0));
+
+        // Root-power levels: 20 * log10, +6.02 dB gain doubles the voltage
+        let voltage = q!(0.0, "dBV")?;
+        assert!(close(voltage.m_as(&u!("volt")?)?, 1.0));
+        let voltage = voltage.checked_add(&q!(20.0 * 2.0f64.log10(), "dB")?)?;
+        assert!(close(voltage.m_as(&u!("volt")?)?, 2.0));
+        let pressure = q!(94.0, "dB_SPL")?;
+        assert!((pressure.m_as(&u!("pascal")?)? - 1.0).abs() < 1.0e-2);
+        let total = pressure.checked_add(&pressure)?;
+        assert!(close(
+            total.m_as(&u!("dB_SPL")?)?,
+            94.0 + 10.0 * 2.0f64.log10()
+        ));
+
+        assert!(matches!(
+            q!(10.0, "dBm")?.checked_add(&q!(1.0, "watt")?),
+            Err(Error::LogarithmicUnitOperation { .. })
+        ));
+        assert!(matches!(
+            q!(3.0, "dB")?.checked_sub(&q!(10.0, "dBm")?),
+            Err(Error::LogarithmicUnitOperation { .. })
+        ));
+        assert!(matches!(
+            q!(10.0, "dBm")?.checked_add(&q!(10.0, "dBV")?),
+            Err(Error::UnitsConversionError { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_logarithmic_units() -> Result<(), Error> {
+        let close = |a: f64, b: f64| (a - b).abs() < 1.0e-9;
+        let decibel = u!("decibel")?;
+
+        assert!(close(q!(1.0, "bel")?.m_as(&decibel)?, 10.0));
+        assert!(close(q!(1.0, "Np")?.m_as(&decibel)?, 20.0 / 10.0f64.ln()));
+        assert!(close(
+            q!(1.0, "decade")?.m_as(&u!("octave")?)?,
+            10.0f64.log2()
+        ));
+
+        // A gain in nepers shifts a level in dBm, and a bel gain a root-power level
+        let level = q!(0.0, "dBm")?.checked_add(&q!(1.0, "neper")?)?;
+        assert!(close(level.m_as(&u!("dBm")?)?, 20.0 / 10.0f64.ln()));
+        let level = q!(0.0, "dBV")?.checked_add(&q!(2.0, "bel")?)?;
+        assert!(close(level.m_as(&u!("volt")?)?, 10.0));
+
+        // Level differences are gains on the scale of the levels
+        let neper_volt = Unit::new(
+            "neper_volt",
+            *u!("volt")?.dimensionality(),
+            UnitTransformation::Logarithmic(LogarithmicTransformation::new(
+                std::f64::consts::E,
+                1.0,
+                1.0,
+            )),
+        );
+        let gain =
+            Quantity::new(2.0, neper_volt.clone()).checked_sub(&Quantity::new(1.0, neper_volt))?;
+        assert_eq!(gain.unit(), &u!("neper")?);
+        assert!(close(gain.m_as(&u!("neper")?)?, 1.0));
+
+        // Gain units are marked in their definition, so user-defined ones shift levels too, and
+        // differences of levels without a defined gain unit stay on their own scale
+        let registry = parse_units_file(
+            "unit binary_gain { dimension: count transformation: log(base: 2, gain: true) prefixes: no }
+            unit binary_watt { dimension: mass * length^2 / time^3 transformation: log(base: 2, ref: 1) prefixes: no }",
+        )?;
+        let binary_gain = registry.parse_unit("binary_gain")?;
+        let binary_watt = registry.parse_unit("binary_watt")?;
+        let level = Quantity::new(1.0, binary_watt.clone())
+            .checked_add(&Quantity::new(2.0, binary_gain.clone()))?;
+        assert!(close(level.m_as(&u!("watt")?)?, 8.0));
+        let gain = Quantity::new(3.0, binary_watt.clone())
+            .checked_sub(&Quantity::new(1.0, binary_watt))?;
+        assert_eq!(gain.unit().name(), "gain(base: 2, factor: 1)");
+        assert!(close(gain.m_as(&binary_gain)?, 2.0));
+
+        // Magnitudes grow as brightness drops: 5 magnitudes are a factor of 100
+        let magnitude = u!("mag")?;
+        assert!(close(magnitude.to_base(5.0), 0.01));
+        assert_eq!(
+            q!(1.0, "mag")?
+                .checked_add(&q!(1.0, "mag")?)?
+                .m_as(&magnitude)?,
+            2.0
+        );
+        assert!(close(
+            q!(1.0, "octave")?
+                .checked_add(&q!(1.0, "decade")?)?
+                .m_as(&u!("octave")?)?,
+            1.0 + 10.0f64.log2()
+        ));
+
+        // Octaves, decades and magnitudes are not gains
+        assert!(matches!(
+            q!(10.0, "dBm")?.checked_add(&q!(1.0, "octave")?),
+            Err(Error::LogarithmicUnitOperation { .. })
+        ));
+        assert_eq!(
+            q!(1.0, "decade")?.checked_sub(&q!(1.0, "bel")?).err(),
+            Some(Error::LogarithmicUnitOperation {
+                name: "decade".into(),
+                operation: "subtraction with a level or gain".into(),
+            })
+        );
+        assert!(matches!(
+            q!(1.0, "dB")?.checked_add(&q!(1.0, "mag")?),
+            Err(Error::LogarithmicUnitOperation { .. })
+        ));
+
+        let acidity = q!(7.0, "pH")?;
+        let concentration = DEFAULT_REGISTRY.parse_unit("mole / liter")?;
+        assert!((acidity.m_as(&concentration)? - 1.0e-7).abs() < 1.0e-18);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_checked_operations() -> Result<(), Error> {
+        let distance = q!(2.0, "kilometer")?;
+        let time = q!(4.0, "second")?;
+
+        assert_eq!(
+            distance.checked_add(&time).err(),
+            Some(Error::UnitsConversionError {
+                expected: LENGTH,
+                got: TIME,
+            })
+        );
+        assert!(distance.checked_sub(&time).is_err());
+        assert_eq!(
+            distance
+                .checked_div(&time)?
+                .m_as(&(u!("meter")? / u!("second")?))?,
+            500.0
+        );
+        assert_eq!(distance.checked_pow(2)?.m_as(&u!("meter")?.pow(2))?, 4.0e6);
+
+        let temperature = q!(20.0, "degree_celsius")?;
+        assert!(matches!(
+            temperature.checked_mul(&distance),
+            Err(Error::BiasedUnitOperation { .. })
+        ));
+        assert!(matches!(
+            temperature.checked_pow(2),
+            Err(Error::BiasedUnitOperation { .. })
+        ));
+        assert!(matches!(
+            q!(3.0, "decibel")?.checked_div(&time),
+            Err(Error::LogarithmicUnitOperation { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reduced_units() -> Result<(), Error> {
+        let distance = q!(3.0, "km")?;
+        let speed = distance.clone() / q!(2.0, "minute")?;
+        let travelled = speed * q!(4.0, "minute")?;
+        assert_eq!(travelled.unit().name(), "((kilometer / minute) * minute)");
+
+        let reduced = travelled.to_reduced_units();
+        assert_eq!(reduced.unit().name(), "kilometer");
+        assert!((reduced.m_as(&u!("km")?)? - 6.0).abs() < 1.0e-12);
+
+        let force = q!(2.0, "kilogram")? * q!(3.0, "meter")? / q!(1.0, "second")?.pow(2);
+        let force = force.simplify(&DEFAULT_REGISTRY);
+        assert_eq!(force.unit().name(), "newton");
+        assert!((force.m_as(&u!("N")?)? - 6.0).abs() < 1.0e-12);
+
+        let pressure = (force / q!(2.0, "meter")?.pow(2)).simplify(&DEFAULT_REGISTRY);
+        assert_eq!(pressure.unit().name(), "pascal");
+
+        let ratio = (distance.clone() / distance).simplify(&DEFAULT_REGISTRY);
+        assert_eq!(ratio.unit().name(), "1");
+        assert_eq!(ratio.base_magnitude(), 1.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_best_prefix() -> Result<(), Error> {
+        let close = |a: f64, b: f64| (a - b).abs() < 1.0e-9 * b.abs();
+
+        let time = q!(0.000012, "second")?.to_compact();
+        assert_eq!(time.unit().symbol(), Some("µs"));
+        assert!(close(time.m_as(time.unit())?, 12.0));
+
+        let size = q!(3.2e9, "byte")?.to_compact();
+        assert_eq!(size.unit().symbol(), Some("GB"));
+        assert!(close(size.m_as(size.unit())?, 3.2));
+
+        // Prefixed units are rescaled within their family, skipping hecto, deca, ...
+        let distance = q!(250.0, "centimeter")?.to_compact();
+        assert_eq!(distance.unit().name(), "meter");
+        assert_eq!(q!(-1500.0, "m")?.to_compact().unit().name(), "kilometer");
+        assert_eq!(q!(1.0e40, "m")?.to_compact().unit().name(), "Quettameter");
+        assert_eq!(q!(1.0e-40, "m")?.to_compact().unit().name(), "quectometer");
+        assert_eq!(q!(0.0, "km")?.to_compact().unit().name(), "meter");
+
+        // Units without prefixes or not in the registry are kept
+        assert_eq!(q!(0.001, "hour")?.to_compact().unit().name(), "hour");
+        let speed = q!(5000.0, "meter")? / q!(1.0, "second")?;
+        assert_eq!(speed.to_compact().unit(), speed.unit());
+
+        let registry = UnitRegistry::new_from_file("src/units.txt")?;
+        let current = Quantity::new(0.05, registry.get("ampere").unwrap().clone());
+        assert_eq!(
+            current.to_best_prefix(&registry).unit().name(),
+            "milliampere"
+        );
+
+        // A `bar` of length is not rescaled as the default `bar` of pressure
+        let mut registry = UnitRegistry::new();
+        registry.load_str(
+            "unit bar { dimension: length transformation: linear(scale: 2) prefixes: standard }",
+            ConflictPolicy::Error,
+        )?;
+        let length = Quantity::new_from_registry(&registry, 0.001, "bar")?;
+        let compact = length.to_compact();
+        assert_eq!(compact.unit(), length.unit());
+        assert_eq!(compact.m_as(length.unit())?, 0.001);
+        assert_eq!(format!("{:#}", length), "0.001 bar");
+        let compact = length.to_best_prefix(&registry);
+        assert_eq!(compact.unit().name(), "millibar");
+        assert_eq!(compact.dimensionality(), &LENGTH);
+        assert!(close(compact.m_as(compact.unit())?, 1.0));
+
+        Ok(())
+    }
+
+    /// American wire gauge: diameter of 0.127 mm at gauge 36, 39 steps per factor of 92.
+    #[derive(Debug)]
+    struct WireGauge;
+
+    impl UnitConversion for WireGauge {
+        fn to_base(&self, value: f64) -> f64 {
+            1.27e-4 * 92.0f64.powf((36.0 - value) / 39.0)
+        }
+
+        fn from_base(&self, value: f64) -> f64 {
+            36.0 - 39.0 * (value / 1.27e-4).log(92.0)
+        }
+    }
+
+    #[test]
+    fn test_custom_units() -> Result<(), Error> {
+        let close = |a: f64, b: f64| (a - b).abs() < 1.0e-9;
+        assert_eq!(
+            WireGauge.check_inverse(&[-3.0, 0.0, 12.0, 40.0], 1.0e-12),
+            Ok(())
+        );
+
+        let mut registry = UnitRegistry::default();
+        registry.register(Unit::new_custom("wire_gauge", LENGTH, WireGauge).with_symbol("AWG"))?;
+        assert!(matches!(
+            registry.allow_prefixes("wire_gauge", "standard"),
+            Err(Error::IncompatiblePrefixes { .. })
+        ));
+        assert!(registry.parse_unit("AWG / second").is_err());
+
+        let gauge = registry.get("AWG").unwrap();
+        let millimeter = registry.get_prefixed("millimeter").unwrap();
+        let wire = Quantity::new(12.0, gauge.clone());
+        assert!(close(wire.m_as(&millimeter)?, 2.0525253885));
+        assert!(close(
+            Quantity::new(0.127, millimeter.into_owned()).m_as(gauge)?,
+            36.0
+        ));
+
+        let time = q!(1.0, "second")?;
+        assert!(matches!(
+            wire.checked_div(&time),
+            Err(Error::CustomUnitOperation { .. })
+        ));
+        assert!(matches!(
+            wire.checked_pow(2),
+            Err(Error::CustomUnitOperation { .. })
+        ));
+
+        Ok(())
+    }
 }
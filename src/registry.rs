@@ -1,34 +1,164 @@
 use crate::errors::ArshinError as Error;
 use crate::fundamentals::Dimension;
-use crate::parser::parse_units_file;
+use crate::parser::{
+    load_units_path, load_units_str, parse_quantity, parse_unit_expression, parse_units_from_path,
+};
+use crate::prefixes::{Prefix, PrefixSet};
+use crate::quantities::Quantity;
+use crate::transformations::UnitTransformation;
 use crate::units::Unit;
 use lazy_static::lazy_static;
-use std::collections::HashMap;
-use std::fs;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
 lazy_static! {
     // Default non-mutable registry
     pub static ref DEFAULT_REGISTRY: UnitRegistry = UnitRegistry::default();
 }
 
+/// What to do when loaded definitions clash with units, aliases or prefix sets already in a
+/// registry (see [`UnitRegistry::load_str`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    /// Fail with `RegistryAlreadyContainsUnit` / `RegistryAlreadyContainsPrefixSet`.
+    #[default]
+    Error,
+    /// Keep the existing definition and ignore the new one.
+    Skip,
+    /// Replace the existing definition with the new one.
+    Override,
+}
+
 /// Registry for storing and retrieving units by name.
 ///
 /// Can be populated manually or from a file via parser.
+/// Aliases (symbols, alternative spellings) map to canonical unit names.
+/// Prefixed units (e.g. `kilometer`, `km`, `kibibyte`) are not stored: they are built at lookup
+/// time for units that accept one or more prefix sets.
+///
+/// A registry created with [`UnitRegistry::from_static`] looks units up in the static tables
+/// first; only units, aliases and prefixes added later are stored in the registry itself.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "RegistryData", into = "RegistryData"))]
 pub struct UnitRegistry {
-    pub units: HashMap<String, Unit>,
+    table: Option<&'static StaticRegistry>,
+    units: HashMap<String, Unit>,
+    aliases: HashMap<String, String>,
+    prefix_sets: Vec<PrefixSet>,
+    prefixable: HashMap<String, Vec<String>>,
+    /// Units, aliases and allowed prefix sets of `table` removed from the registry.
+    removed: HashSet<String>,
+    /// Changes made while loading units files, undone if loading fails.
+    journal: Option<Vec<Undo>>,
+}
+
+/// Previous state of an entry of the registry.
+#[derive(Clone)]
+enum Undo {
+    Unit(String, Option<Unit>),
+    Alias(String, Option<String>),
+    Prefixable(String, Option<Vec<String>>),
+    Removed(String),
+    PrefixSets(Vec<PrefixSet>),
+}
+
+/// Serialized form of a registry: all its units, aliases, prefix sets and allowed prefix sets.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct RegistryData {
+    units: HashMap<String, Unit>,
+    aliases: HashMap<String, String>,
+    prefix_sets: Vec<PrefixSet>,
+    prefixable: HashMap<String, Vec<String>>,
+}
+
+#[cfg(feature = "serde")]
+impl From<UnitRegistry> for RegistryData {
+    fn from(registry: UnitRegistry) -> Self {
+        Self {
+            units: registry
+                .units()
+                .map(|unit| (unit.name().to_string(), unit.clone()))
+                .collect(),
+            aliases: registry
+                .aliases()
+                .map(|(alias, name)| (alias.to_string(), name.to_string()))
+                .collect(),
+            prefixable: registry.prefixable(),
+            prefix_sets: registry.prefix_sets,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<RegistryData> for UnitRegistry {
+    fn from(data: RegistryData) -> Self {
+        Self {
+            table: None,
+            units: data.units,
+            aliases: data.aliases,
+            prefix_sets: data.prefix_sets,
+            prefixable: data.prefixable,
+            removed: HashSet::new(),
+            journal: None,
+        }
+    }
 }
 
+/// Default units database, compiled into the library.
+pub const DEFAULT_UNITS: &str = include_str!("units.txt");
+
+// `DEFAULT_TABLE`, generated from `units.txt` by the build script.
+include!(concat!(env!("OUT_DIR"), "/default_units.rs"));
+
+/// Registry of the default units database, built from static tables without parsing or touching
+/// the filesystem.
 impl Default for UnitRegistry {
     fn default() -> Self {
-        Self::new_from_file("src/units.txt").unwrap()
+        Self::from_static(&DEFAULT_TABLE)
+    }
+}
+
+/// Units database compiled into static perfect-hash tables: [`DEFAULT_TABLE`] for the default
+/// units file, or tables generated for user files with `arshin::codegen` (`codegen` feature).
+///
+/// Lookups by name, symbol or alias need no parsing or allocation; use
+/// [`UnitRegistry::from_static`] to also resolve prefixed names.
+pub struct StaticRegistry {
+    pub units: phf::Map<&'static str, Unit>,
+    pub aliases: phf::Map<&'static str, &'static str>,
+    pub prefixable: phf::Map<&'static str, &'static [&'static str]>,
+    /// Prefix sets besides `standard` and `binary`.
+    pub prefix_sets: &'static [StaticPrefixSet],
+}
+
+/// Prefix set in a [`StaticRegistry`]: name, then name, symbol and factor of each prefix.
+pub type StaticPrefixSet = (&'static str, &'static [(&'static str, &'static str, f64)]);
+
+impl StaticRegistry {
+    /// Gets a unit by name, symbol or alias; prefixed names are not resolved.
+    pub fn get(&self, name: &str) -> Option<&Unit> {
+        self.units.get(name).or_else(|| {
+            self.aliases
+                .get(name)
+                .and_then(|canonical| self.units.get(canonical))
+        })
     }
 }
 
 impl UnitRegistry {
-    /// Creates an empty registry.
+    /// Creates an empty registry with the `standard` (SI) and `binary` (IEC) prefix sets.
     pub fn new() -> Self {
         Self {
+            table: None,
             units: HashMap::new(),
+            aliases: HashMap::new(),
+            prefix_sets: vec![PrefixSet::standard(), PrefixSet::binary()],
+            prefixable: HashMap::new(),
+            removed: HashSet::new(),
+            journal: None,
         }
     }
 
@@ -42,88 +172,649 @@ impl UnitRegistry {
     ///
     /// # Errors
     /// - File read errors.
-    /// - Parse errors.
+    /// - Parse errors, import cycles and duplicate definitions.
     pub fn new_from_file(file_name: &str) -> Result<UnitRegistry, Error> {
-        let file_content = fs::read_to_string(file_name).map_err(|e| Error::OSError {
-            message: e.to_string(),
-        })?;
+        parse_units_from_path(file_name)
+    }
+
+    /// Creates a registry from static tables without parsing or copying them: lookups go to the
+    /// tables first, then to units registered later.
+    pub fn from_static(table: &'static StaticRegistry) -> Self {
+        let mut registry = Self {
+            table: Some(table),
+            ..Self::new()
+        };
+        for (name, prefixes) in table.prefix_sets {
+            registry.remove_prefix_set(name);
+            registry
+                .prefix_sets
+                .push(PrefixSet::from_table(name, prefixes));
+        }
+
+        registry
+    }
+
+    /// Parses units file content into this registry.
+    ///
+    /// Definitions can refer to units already in the registry, e.g. `unit arshin = 0.7112 meter`
+    /// on top of the default registry. Clashing names are handled according to `policy`; an
+    /// overridden unit is removed with all its aliases, while units already derived from it keep
+    /// their values. On error the changes made so far are undone, leaving the registry unchanged.
+    ///
+    /// `import "path"` directives are resolved relative to the current directory; use
+    /// [`UnitRegistry::load_file`] to resolve them relative to a file.
+    ///
+    /// # Parameters
+    /// - `file_content`: String content in the units file format.
+    /// - `policy`: How to handle conflicts with existing definitions.
+    ///
+    /// # Errors
+    /// - Parse errors (see [`parse_units_file`](crate::parser::parse_units_file)).
+    /// - Conflicts with existing definitions under `ConflictPolicy::Error`.
+    pub fn load_str(&mut self, file_content: &str, policy: ConflictPolicy) -> Result<(), Error> {
+        self.journaled(|registry| load_units_str(registry, file_content, policy))
+    }
+
+    /// Parses a units file (and its imports) into this registry; see [`UnitRegistry::load_str`].
+    ///
+    /// # Errors
+    /// - File read errors, import cycles.
+    /// - Parse errors and conflicts as in [`UnitRegistry::load_str`].
+    pub fn load_file(
+        &mut self,
+        file_name: impl AsRef<Path>,
+        policy: ConflictPolicy,
+    ) -> Result<(), Error> {
+        self.journaled(|registry| load_units_path(registry, file_name.as_ref(), policy))
+    }
+
+    /// Runs `load`, undoing its changes if it fails.
+    fn journaled(
+        &mut self,
+        load: impl FnOnce(&mut Self) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        let outer = self.journal.replace(Vec::new());
+        let result = load(self);
+        let journal = std::mem::replace(&mut self.journal, outer).unwrap_or_default();
+
+        match (&result, &mut self.journal) {
+            (Err(_), _) => {
+                for undo in journal.into_iter().rev() {
+                    match undo {
+                        Undo::Unit(name, Some(unit)) => {
+                            self.units.insert(name, unit);
+                        }
+                        Undo::Unit(name, None) => {
+                            self.units.remove(&name);
+                        }
+                        Undo::Alias(alias, Some(name)) => {
+                            self.aliases.insert(alias, name);
+                        }
+                        Undo::Alias(alias, None) => {
+                            self.aliases.remove(&alias);
+                        }
+                        Undo::Prefixable(name, Some(sets)) => {
+                            self.prefixable.insert(name, sets);
+                        }
+                        Undo::Prefixable(name, None) => {
+                            self.prefixable.remove(&name);
+                        }
+                        Undo::Removed(name) => {
+                            self.removed.remove(&name);
+                        }
+                        Undo::PrefixSets(prefix_sets) => self.prefix_sets = prefix_sets,
+                    }
+                }
+            }
+            (Ok(_), Some(outer)) => outer.extend(journal),
+            (Ok(_), None) => {}
+        }
+
+        result
+    }
+
+    fn record(&mut self, undo: impl FnOnce() -> Undo) {
+        if let Some(journal) = &mut self.journal {
+            journal.push(undo());
+        }
+    }
+
+    fn set_unit(&mut self, name: &str, unit: Option<Unit>) -> Option<Unit> {
+        let previous = match unit {
+            Some(unit) => self.units.insert(name.into(), unit),
+            None => self.units.remove(name),
+        };
+        self.record(|| Undo::Unit(name.into(), previous.clone()));
+        previous
+    }
+
+    fn set_alias(&mut self, alias: &str, name: Option<&str>) -> Option<String> {
+        let previous = match name {
+            Some(name) => self.aliases.insert(alias.into(), name.into()),
+            None => self.aliases.remove(alias),
+        };
+        self.record(|| Undo::Alias(alias.into(), previous.clone()));
+        previous
+    }
+
+    fn set_prefixable(&mut self, name: &str, sets: Option<Vec<String>>) {
+        let previous = match sets {
+            Some(sets) => self.prefixable.insert(name.into(), sets),
+            None => self.prefixable.remove(name),
+        };
+        self.record(|| Undo::Prefixable(name.into(), previous));
+    }
+
+    /// Hides a unit, alias and allowed prefix sets of the static tables.
+    fn hide(&mut self, name: &str) {
+        if self.table.is_some() && self.removed.insert(name.into()) {
+            self.record(|| Undo::Removed(name.into()));
+        }
+    }
+
+    fn set_prefix_sets(&mut self, change: impl FnOnce(&mut Vec<PrefixSet>)) {
+        if self.journal.is_some() {
+            let previous = self.prefix_sets.clone();
+            self.record(|| Undo::PrefixSets(previous));
+        }
+        change(&mut self.prefix_sets);
+    }
+
+    /// Registered units (without prefixed units), in no particular order.
+    pub fn units(&self) -> impl Iterator<Item = &Unit> + Clone {
+        let table = self.table.into_iter().flat_map(|table| {
+            table
+                .units
+                .entries()
+                .filter(|(name, _)| !self.removed.contains(**name))
+                .map(|(_, unit)| unit)
+        });
+        self.units.values().chain(table)
+    }
+
+    /// Registered aliases and symbols with the names of their units, in no particular order.
+    pub fn aliases(&self) -> impl Iterator<Item = (&str, &str)> {
+        let table = self.table.into_iter().flat_map(|table| {
+            table
+                .aliases
+                .entries()
+                .filter(|(alias, _)| !self.removed.contains(**alias))
+                .map(|(alias, name)| (*alias, *name))
+        });
+        self.aliases
+            .iter()
+            .map(|(alias, name)| (alias.as_str(), name.as_str()))
+            .chain(table)
+    }
 
-        let registry = parse_units_file(&file_content).map_err(|e| Error::PestParseError {
-            message: e.to_string(),
-        })?;
+    /// Registered prefix sets.
+    pub fn prefix_sets(&self) -> &[PrefixSet] {
+        &self.prefix_sets
+    }
 
-        Ok(registry)
+    /// Names of the prefix sets allowed on a unit (none for unknown or unprefixable units).
+    pub fn accepted_prefix_sets(&self, name: &str) -> impl Iterator<Item = &str> {
+        let registered = self.prefixable.get(name);
+        let table = match registered {
+            Some(_) => None,
+            None => self
+                .table
+                .filter(|_| !self.removed.contains(name))
+                .and_then(|table| table.prefixable.get(name)),
+        };
+
+        registered
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .chain(table.into_iter().flat_map(|sets| sets.iter().copied()))
     }
 
+    /// Allowed prefix sets of all units accepting prefixes.
+    #[cfg(any(feature = "codegen", feature = "serde"))]
+    pub(crate) fn prefixable(&self) -> HashMap<String, Vec<String>> {
+        self.units()
+            .map(|unit| {
+                let sets: Vec<String> = self
+                    .accepted_prefix_sets(unit.name())
+                    .map(String::from)
+                    .collect();
+                (unit.name().to_string(), sets)
+            })
+            .filter(|(_, sets)| !sets.is_empty())
+            .collect()
+    }
+
+    /// Lists unit names, including prefixed names of units accepting prefixes.
     pub fn unit_names(&self) -> impl Iterator<Item = String> {
-        self.units.keys().map(|s| s.clone())
+        self.units()
+            .map(|unit| unit.name().to_string())
+            .chain(self.prefixed_names(|_| true))
     }
 
     pub fn unit_names_with_dimensionality(&self, dim: Dimension) -> impl Iterator<Item = String> {
-        self.units
-            .iter()
-            .filter(move |(_, unit)| *unit.dimensionality() == dim)
-            .map(|(s, _)| s.clone())
+        self.units()
+            .filter(move |unit| *unit.dimensionality() == dim)
+            .map(|unit| unit.name().to_string())
+            .chain(self.prefixed_names(move |unit| *unit.dimensionality() == dim))
     }
 
-    /// Registers a unit.
+    fn prefixed_names(&self, filter: impl Fn(&Unit) -> bool) -> impl Iterator<Item = String> {
+        self.units()
+            .filter(move |unit| filter(unit))
+            .flat_map(|unit| {
+                self.accepted_prefixes(unit.name())
+                    .map(move |prefix| format!("{}{}", prefix.name, unit.name()))
+            })
+    }
+
+    /// Registers a unit; its symbol, if any, is registered as an alias.
+    ///
+    /// Registered names and aliases take precedence over prefixed names built at lookup time.
     ///
     /// # Errors
-    /// If the name already exists.
+    /// If the name or symbol already exists.
     pub fn register(&mut self, unit: Unit) -> Result<(), Error> {
-        let name: String = unit.name.to_string();
-        if self.contains(unit.name()) {
-            return Err(Error::RegistryAlreadyContainsUnit { name: name });
+        let name: String = unit.name().to_string();
+        if self.contains_exact(unit.name()) {
+            return Err(Error::RegistryAlreadyContainsUnit { name });
+        }
+
+        if let Some(symbol) = unit.symbol().filter(|symbol| *symbol != name) {
+            if self.contains_exact(symbol) {
+                return Err(Error::RegistryAlreadyContainsUnit {
+                    name: symbol.into(),
+                });
+            }
+            self.set_alias(symbol, Some(&name));
+        }
+
+        self.set_unit(&name, Some(unit));
+        Ok(())
+    }
+
+    /// Registers an alternative name (symbol, spelling) for a registered unit.
+    ///
+    /// # Errors
+    /// If the alias is already taken or the unit is not registered.
+    pub fn register_alias(&mut self, alias: &str, name: &str) -> Result<(), Error> {
+        if self.contains_exact(alias) {
+            return Err(Error::RegistryAlreadyContainsUnit { name: alias.into() });
+        }
+        if self.unit(name).is_none() {
+            return Err(Error::RegistryDoesNotContainUnit { name: name.into() });
         }
 
-        self.units.insert(name, unit);
+        self.set_alias(alias, Some(name));
         Ok(())
     }
 
+    /// Removes an alternative name of a unit, returning the name of the unit it referred to.
+    ///
+    /// Removing the symbol of a unit also removes the symbol from the unit.
+    pub fn remove_alias(&mut self, alias: &str) -> Option<String> {
+        let name = match self.set_alias(alias, None) {
+            Some(name) => name,
+            None => {
+                let name = self.table_alias(alias)?;
+                self.hide(alias);
+                name.to_string()
+            }
+        };
+
+        if let Some(unit) = self.unit(&name)
+            && unit.symbol() == Some(alias)
+        {
+            let unit = unit.clone().without_symbol();
+            if self.table_unit(&name).is_some() {
+                let sets: Vec<String> =
+                    self.accepted_prefix_sets(&name).map(String::from).collect();
+                if !sets.is_empty() {
+                    self.set_prefixable(&name, Some(sets));
+                }
+                self.hide(&name);
+            }
+            self.set_unit(&name, Some(unit));
+        }
+
+        Some(name)
+    }
+
+    /// Removes a unit by its name, together with its aliases and allowed prefixes.
+    pub fn remove(&mut self, name: &str) -> Option<Unit> {
+        let unit = match self.set_unit(name, None) {
+            Some(unit) => unit,
+            None => {
+                let unit = self.table_unit(name)?.clone();
+                self.hide(name);
+                unit
+            }
+        };
+
+        let aliases: Vec<String> = self
+            .aliases
+            .iter()
+            .filter(|(_, canonical)| *canonical == name)
+            .map(|(alias, _)| alias.clone())
+            .collect();
+        for alias in aliases {
+            self.set_alias(&alias, None);
+        }
+        if let Some(table) = self.table {
+            for (alias, canonical) in table.aliases.entries() {
+                if *canonical == name {
+                    self.hide(alias);
+                }
+            }
+        }
+        if self.prefixable.contains_key(name) {
+            self.set_prefixable(name, None);
+        }
+        Some(unit)
+    }
+
+    /// Registers a prefix set that units can opt into with `allow_prefixes`.
+    ///
+    /// # Errors
+    /// If a prefix set with the same name already exists or the name is the reserved `no`.
+    pub fn register_prefix_set(&mut self, prefix_set: PrefixSet) -> Result<(), Error> {
+        if prefix_set.name() == "no" || self.prefix_set(prefix_set.name()).is_some() {
+            return Err(Error::RegistryAlreadyContainsPrefixSet {
+                name: prefix_set.name().into(),
+            });
+        }
+
+        self.set_prefix_sets(|prefix_sets| prefix_sets.push(prefix_set));
+        Ok(())
+    }
+
+    /// Removes a prefix set by name; units keep referring to it until it is registered again.
+    pub fn remove_prefix_set(&mut self, name: &str) -> Option<PrefixSet> {
+        let index = self.prefix_sets.iter().position(|set| set.name() == name)?;
+        let mut removed = None;
+        self.set_prefix_sets(|prefix_sets| removed = Some(prefix_sets.remove(index)));
+        removed
+    }
+
+    /// Gets a prefix set by name.
+    pub fn prefix_set(&self, name: &str) -> Option<&PrefixSet> {
+        self.prefix_sets.iter().find(|set| set.name() == name)
+    }
+
+    /// Allows prefixes of a prefix set on a registered unit
+    /// (e.g. `standard`: `kilo` + `meter`, `k` + `m`; `binary`: `kibi` + `byte`, `Ki` + `B`).
+    ///
+    /// # Errors
+    /// - If the unit or prefix set is not registered.
+    /// - If the unit has a biased, logarithmic or custom transformation.
+    /// - If a prefix name or symbol of the set is already provided by another set allowed on the unit.
+    pub fn allow_prefixes(&mut self, name: &str, prefix_set: &str) -> Result<(), Error> {
+        if self.prefix_set(prefix_set).is_none() {
+            return Err(Error::UnknownPrefixSet {
+                name: prefix_set.into(),
+            });
+        }
+
+        let unit = self
+            .unit(name)
+            .ok_or(Error::RegistryDoesNotContainUnit { name: name.into() })?;
+
+        match unit.transformation() {
+            UnitTransformation::Logarithmic(_) | UnitTransformation::Custom(_) => {
+                return Err(Error::IncompatiblePrefixes { name: name.into() });
+            }
+            UnitTransformation::Linear(transformation) if transformation.offset() != 0.0 => {
+                return Err(Error::IncompatiblePrefixes { name: name.into() });
+            }
+            _ => {}
+        }
+
+        let mut allowed: Vec<String> = self.accepted_prefix_sets(name).map(String::from).collect();
+        if allowed.iter().any(|set| set == prefix_set) {
+            return Ok(());
+        }
+        if let Some(prefix) = self.prefix_collision(&allowed, prefix_set) {
+            return Err(Error::PrefixCollision {
+                name: name.into(),
+                prefix,
+            });
+        }
+
+        allowed.push(prefix_set.into());
+        self.set_prefixable(name, Some(allowed));
+        Ok(())
+    }
+
+    /// Finds a prefix name or symbol of `prefix_set` that is also defined by one of `allowed`.
+    fn prefix_collision(&self, allowed: &[String], prefix_set: &str) -> Option<String> {
+        let new_prefixes = self.prefix_set(prefix_set)?.prefixes();
+        allowed
+            .iter()
+            .filter_map(|set| self.prefix_set(set))
+            .flat_map(|set| set.prefixes())
+            .find_map(|existing| {
+                new_prefixes.iter().find_map(|prefix| {
+                    if prefix.name == existing.name {
+                        Some(prefix.name.clone())
+                    } else if !prefix.symbol.is_empty() && prefix.symbol == existing.symbol {
+                        Some(prefix.symbol.clone())
+                    } else {
+                        None
+                    }
+                })
+            })
+    }
+
     pub fn contains(&self, name: &str) -> bool {
-        self.units.contains_key(name)
+        self.contains_exact(name) || self.build_prefixed(name).is_some()
     }
 
-    /// Gets a unit by name.
+    pub(crate) fn contains_exact(&self, name: &str) -> bool {
+        self.unit(name).is_some() || self.canonical_name(name).is_some()
+    }
+
+    /// Gets a registered unit by name, symbol or alias.
+    ///
+    /// Prefixed units (e.g. `kilometer`) are not registered; see [`UnitRegistry::get_prefixed`].
     pub fn get(&self, name: &str) -> Option<&Unit> {
-        self.units.get(name)
+        self.unit(name).or_else(|| {
+            self.canonical_name(name)
+                .and_then(|canonical| self.unit(canonical))
+        })
+    }
+
+    /// Gets a unit by its canonical name.
+    fn unit(&self, name: &str) -> Option<&Unit> {
+        self.units.get(name).or_else(|| self.table_unit(name))
+    }
+
+    /// Name of the unit an alias or symbol refers to.
+    fn canonical_name(&self, alias: &str) -> Option<&str> {
+        self.aliases
+            .get(alias)
+            .map(String::as_str)
+            .or_else(|| self.table_alias(alias))
+    }
+
+    fn table_unit(&self, name: &str) -> Option<&'static Unit> {
+        self.table
+            .filter(|_| !self.removed.contains(name))
+            .and_then(|table| table.units.get(name))
+    }
+
+    fn table_alias(&self, alias: &str) -> Option<&'static str> {
+        self.table
+            .filter(|_| !self.removed.contains(alias))
+            .and_then(|table| table.aliases.get(alias).copied())
+    }
+
+    /// Gets a unit like [`UnitRegistry::get`], or builds a prefixed unit (e.g. `kilometer`,
+    /// `km`) if the name is a prefix followed by a unit accepting it.
+    pub fn get_prefixed(&self, name: &str) -> Option<Cow<'_, Unit>> {
+        match self.get(name) {
+            Some(unit) => Some(Cow::Borrowed(unit)),
+            None => self.build_prefixed(name).map(Cow::Owned),
+        }
+    }
+
+    /// Splits a name into prefix + unit: prefix names combine with unit names and aliases
+    /// (`kilometre`), prefix symbols with unit symbols (`km`).
+    fn build_prefixed(&self, name: &str) -> Option<Unit> {
+        self.prefix_sets.iter().find_map(|set| {
+            set.prefixes()
+                .iter()
+                .find_map(|prefix| self.get_with_prefix(name, set.name(), prefix))
+        })
+    }
+
+    fn get_with_prefix(&self, name: &str, set: &str, prefix: &Prefix) -> Option<Unit> {
+        let accepts = |unit: &Unit| self.accepted_prefix_sets(unit.name()).any(|s| s == set);
+
+        if let Some(rest) = name.strip_prefix(prefix.name.as_str())
+            && let Some(unit) = self.get(rest)
+            && accepts(unit)
+            && (unit.name() == rest || unit.symbol() != Some(rest))
+        {
+            return Some(unit.with_prefix(prefix));
+        }
+
+        if !prefix.symbol.is_empty()
+            && let Some(rest) = name.strip_prefix(prefix.symbol.as_str())
+            && let Some(unit) = self.get(rest)
+            && accepts(unit)
+            && unit.symbol() == Some(rest)
+        {
+            return Some(unit.with_prefix(prefix));
+        }
+
+        None
+    }
+
+    /// Prefixes of all prefix sets allowed on a unit (none for unknown or unprefixable units).
+    pub fn accepted_prefixes(&self, name: &str) -> impl Iterator<Item = &Prefix> {
+        self.accepted_prefix_sets(name)
+            .filter_map(|set| self.prefix_set(set))
+            .flat_map(|set| set.prefixes())
+    }
+
+    /// Splits a unit name built by the registry into its unprefixed unit and prefix
+    /// (e.g. `kilometer` → `meter`, `kilo`).
+    ///
+    /// Prefixes are tried in the order of [`UnitRegistry::get_prefixed`], so an ambiguous name
+    /// splits like the unit it builds.
+    ///
+    /// # Returns
+    /// `None` if the name is not a unit of the registry or one of its prefixed units.
+    pub fn unprefixed(&self, name: &str) -> Option<(&Unit, Option<&Prefix>)> {
+        if let Some(unit) = self.unit(name) {
+            return Some((unit, None));
+        }
+
+        self.prefix_sets.iter().find_map(|set| {
+            set.prefixes().iter().find_map(|prefix| {
+                let unit = self.unit(name.strip_prefix(prefix.name.as_str())?)?;
+                self.accepted_prefix_sets(unit.name())
+                    .any(|accepted| accepted == set.name())
+                    .then_some((unit, Some(prefix)))
+            })
+        })
+    }
+
+    /// Finds a unit with the given dimensionality and scale (e.g. `newton` for `MASS * LENGTH /
+    /// TIME.pow(2)` and 1), building prefixed units if no unit matches unprefixed
+    /// (e.g. `Megajoule`).
+    ///
+    /// Among several matches, units with a symbol and then shorter names are preferred
+    /// (e.g. `degree_kelvin` over `delta_degree_celsius`).
+    ///
+    /// # Returns
+    /// `None` if no multiplicative unit (identity or linear without offset) matches.
+    pub fn find_unit(&self, dimension: Dimension, scale: f64) -> Option<Unit> {
+        let matches = |unit_scale: f64| (unit_scale - scale).abs() <= 1.0e-9 * scale.abs();
+        let rank = |unit: &Unit| {
+            (
+                unit.symbol().is_none(),
+                unit.name().chars().count(),
+                unit.name().to_string(),
+            )
+        };
+
+        let candidates = self
+            .units()
+            .filter(|unit| *unit.dimensionality() == dimension)
+            .filter_map(|unit| unit.scale().map(|unit_scale| (unit, unit_scale)));
+
+        let unprefixed = candidates
+            .clone()
+            .filter(|(_, unit_scale)| matches(*unit_scale))
+            .map(|(unit, _)| unit.clone())
+            .min_by_key(rank);
+        if unprefixed.is_some() {
+            return unprefixed;
+        }
+
+        candidates
+            .flat_map(|(unit, unit_scale)| {
+                self.accepted_prefixes(unit.name())
+                    .filter(move |prefix| matches(unit_scale * prefix.factor))
+                    .map(|prefix| unit.with_prefix(prefix))
+            })
+            .min_by_key(rank)
+    }
+
+    /// Parses a unit expression (e.g. `kilogram * meter / second^2`) against this registry.
+    ///
+    /// # Errors
+    /// Syntax errors, unknown units or non-multiplicative units in compound expressions.
+    pub fn parse_unit(&self, expression: &str) -> Result<Unit, Error> {
+        parse_unit_expression(self, expression)
+    }
+
+    /// Parses a quantity (e.g. `9.81 meter / second^2`) against this registry.
+    ///
+    /// # Errors
+    /// Malformed magnitude, missing unit or unit expression errors.
+    pub fn parse_quantity(&self, input: &str) -> Result<Quantity<f64>, Error> {
+        parse_quantity(self, input)
     }
 }
 
 /// Macro to get a unit from a registry (or default).
 ///
+/// The registry can be any expression evaluating to a `UnitRegistry` or a reference to one; the
+/// unit name can be a `&str` or a `String`. Returns `Result<Unit, ArshinError>`.
+///
 /// # Examples
-/// `u!("meter")` or `u!(registry, "meter")`.
+/// `u!("meter")`, `u!(registry, "meter")` or `u!(&self.registry, name)`.
 #[macro_export]
 macro_rules! u {
-    ($registry:ident, $unit_name:expr) => {
-        $registry.get($unit_name).map(|unit| unit.clone()).ok_or(
-            Error::RegistryDoesNotContainUnit {
-                name: $unit_name.into(),
-            },
-        )
-    };
+    ($registry:expr, $unit_name:expr) => {{
+        let name = $unit_name;
+        $registry
+            .get_prefixed(&name)
+            .map(|unit| unit.into_owned())
+            .ok_or_else(|| $crate::errors::ArshinError::RegistryDoesNotContainUnit {
+                name: name.to_string(),
+            })
+    }};
 
     ($unit_name:expr) => {
-        DEFAULT_REGISTRY
-            .get($unit_name)
-            .map(|unit| unit.clone())
-            .ok_or(Error::RegistryDoesNotContainUnit {
-                name: $unit_name.into(),
-            })
+        $crate::u!(*$crate::registry::DEFAULT_REGISTRY, $unit_name)
     };
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::fundamentals::base::{LENGTH, TEMPERATURE};
+    use crate::fundamentals::base::{LENGTH, MASS, TEMPERATURE, TIME};
+    use crate::parser::parse_units_file;
     use std::collections::HashSet;
+    use std::fs;
 
     #[test]
     fn test_registry_create() {
         let registry = UnitRegistry::new();
-        assert!(registry.units.is_empty());
+        assert_eq!(registry.units().count(), 0);
     }
 
     #[test]
@@ -132,12 +823,57 @@ mod tests {
 
         assert!(registry.contains("millimeter"));
         assert!(registry.get("meter").is_some());
-        assert!(registry.get("kilometer").is_some());
+        assert!(registry.get_prefixed("kilometer").is_some());
         assert!(registry.get("degree_celsius").is_some());
         assert!(registry.get("decibel").is_some());
         assert!(registry.get("newton").is_some());
     }
 
+    #[test]
+    fn test_registry_symbols() {
+        let registry = UnitRegistry::new_from_file("src/units.txt").unwrap();
+
+        assert_eq!(registry.get_prefixed("km").unwrap().name(), "kilometer");
+        assert_eq!(registry.get_prefixed("ms").unwrap().name(), "millisecond");
+        assert_eq!(registry.get("°C").unwrap().name(), "degree_celsius");
+        assert_eq!(registry.get_prefixed("kB").unwrap().name(), "kilobyte");
+        assert_eq!(registry.get("dB").unwrap().name(), "decibel");
+    }
+
+    #[test]
+    fn test_registry_binary_prefixes() {
+        let registry = UnitRegistry::new_from_file("src/units.txt").unwrap();
+
+        let kibibyte = registry.get_prefixed("KiB").unwrap();
+        assert_eq!(kibibyte.name(), "kibibyte");
+        assert_eq!(kibibyte.to_base(1.0), 8.0 * 1024.0);
+        assert_eq!(
+            registry.get_prefixed("gibibit").unwrap().to_base(1.0),
+            1073741824.0
+        );
+        assert_eq!(registry.get_prefixed("MB").unwrap().to_base(1.0), 8.0e6);
+        assert!(registry.get_prefixed("kibimeter").is_none());
+    }
+
+    #[test]
+    fn test_registry_relative_definitions() {
+        let registry = UnitRegistry::new_from_file("src/units.txt").unwrap();
+
+        let joule = registry.get("joule").unwrap();
+        let product = registry.parse_unit("newton * meter").unwrap();
+        assert!(joule.compatible(&product));
+        assert_eq!(joule.to_base(1.0), product.to_base(1.0));
+
+        let kilowatt = registry.get_prefixed("kW").unwrap();
+        assert_eq!(kilowatt.name(), "kilowatt");
+        assert_eq!(kilowatt.symbol(), Some("kW"));
+        assert_eq!(
+            *kilowatt.dimensionality(),
+            MASS * LENGTH.pow(2) / TIME.pow(3)
+        );
+        assert_eq!(kilowatt.to_base(1.0), 1.0e3);
+    }
+
     #[test]
     fn test_registry_register() {
         let mut registry = UnitRegistry::new();
@@ -151,7 +887,7 @@ mod tests {
         registry.register(celsius).unwrap();
 
         // Getting by name
-        if let Some(unit) = registry.get("kilometer") {
+        if let Some(unit) = registry.get_prefixed("kilometer") {
             println!("Found: {}", unit.name());
             let base = unit.to_base(5.0); // 5 km → 5000 m
             println!("5 km = {} in base units", base);
@@ -166,11 +902,243 @@ mod tests {
         assert!(registry.register(meter.clone()).is_err());
     }
 
+    #[test]
+    fn test_register_alias() {
+        let mut registry = UnitRegistry::new();
+        registry
+            .register(Unit::new_base("meter", LENGTH).with_symbol("m"))
+            .unwrap();
+        registry.register_alias("metre", "meter").unwrap();
+
+        assert_eq!(registry.get("m").unwrap().name(), "meter");
+        assert_eq!(registry.get("metre").unwrap().name(), "meter");
+        assert!(registry.register_alias("m", "meter").is_err());
+        assert!(registry.register_alias("ft", "foot").is_err());
+        assert!(registry.register(Unit::new_base("metre", LENGTH)).is_err());
+
+        assert_eq!(registry.remove_alias("metre"), Some("meter".to_string()));
+        assert_eq!(registry.remove_alias("metre"), None);
+        assert!(registry.get("metre").is_none());
+        assert!(registry.register(Unit::new_base("metre", LENGTH)).is_ok());
+    }
+
+    #[test]
+    fn test_lazy_prefixes() {
+        let mut registry = UnitRegistry::new();
+        registry
+            .register(Unit::new_linear("parrot", LENGTH, 0.3, 0.0).with_symbol("pr"))
+            .unwrap();
+        assert!(registry.get_prefixed("kiloparrot").is_none());
+
+        registry.allow_prefixes("parrot", "standard").unwrap();
+        let kiloparrot = registry.get_prefixed("kiloparrot").unwrap();
+        assert_eq!(kiloparrot.to_base(1.0), 300.0);
+        assert_eq!(registry.get_prefixed("kpr"), Some(kiloparrot));
+        assert!(registry.get_prefixed("kparrot").is_none());
+        assert_eq!(registry.units().count(), 1);
+        assert!(registry.get_prefixed("kibiparrot").is_none());
+        assert_eq!(
+            registry.allow_prefixes("parrot", "imperial"),
+            Err(Error::UnknownPrefixSet {
+                name: "imperial".into()
+            })
+        );
+
+        registry
+            .register(Unit::new_linear("celsius", TEMPERATURE, 1.0, 273.15))
+            .unwrap();
+        assert_eq!(
+            registry.allow_prefixes("celsius", "standard"),
+            Err(Error::IncompatiblePrefixes {
+                name: "celsius".into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_load_str() {
+        let mut registry = UnitRegistry::new_from_file("src/units.txt").unwrap();
+        registry
+            .load_str(
+                "unit parrot = 0.3 meter { prefixes: standard }",
+                ConflictPolicy::Error,
+            )
+            .unwrap();
+        assert!((registry.get_prefixed("kiloparrot").unwrap().to_base(1.0) - 300.0).abs() < 1.0e-9);
+        assert!(registry.get("newton").is_some());
+
+        let redefinition = "unit foot = 0.3 meter { symbol: ft }";
+        assert_eq!(
+            registry.load_str(redefinition, ConflictPolicy::Error),
+            Err(Error::DefinitionError {
+                file: "<string>".into(),
+                line: 1,
+                column: 1,
+                name: "foot".into(),
+                error: Box::new(Error::RegistryAlreadyContainsUnit {
+                    name: "foot".into()
+                }),
+            })
+        );
+
+        registry
+            .load_str(redefinition, ConflictPolicy::Skip)
+            .unwrap();
+        assert_eq!(registry.get("ft").unwrap().to_base(1.0), 0.3048);
+
+        registry
+            .load_str(redefinition, ConflictPolicy::Override)
+            .unwrap();
+        assert_eq!(registry.get("ft").unwrap().to_base(1.0), 0.3);
+        assert!(registry.get("feet").is_none());
+    }
+
+    #[test]
+    fn test_load_str_is_atomic() {
+        let mut registry = UnitRegistry::new();
+        let result = registry.load_str(
+            "unit meter { dimension: length transformation: identity prefixes: no }
+             unit inch = 2.54 centimeter",
+            ConflictPolicy::Error,
+        );
+        assert!(result.is_err());
+        assert!(registry.get("meter").is_none());
+    }
+
+    #[test]
+    fn test_load_str_undoes_overrides() {
+        let mut registry = UnitRegistry::default();
+        let result = registry.load_str(
+            "prefix_set russian { pol = 0.5 }
+             unit foot = 0.3 meter { prefixes: russian symbol: ft }
+             unit meter_squared = meter^2 { symbol: m }
+             unit inch = 2.54 parrot",
+            ConflictPolicy::Override,
+        );
+        assert!(result.is_err());
+        assert_eq!(registry.get("ft").unwrap().to_base(1.0), 0.3048);
+        assert_eq!(registry.get("feet").unwrap().name(), "foot");
+        assert_eq!(registry.get("m").unwrap().symbol(), Some("m"));
+        assert_eq!(registry.get("inch"), DEFAULT_REGISTRY.get("inch"));
+        assert!(registry.get("meter_squared").is_none());
+        assert!(registry.prefix_set("russian").is_none());
+        assert_eq!(registry.units.len(), 0);
+        assert_eq!(registry.aliases.len(), 0);
+        assert!(registry.removed.is_empty());
+        assert!(registry.journal.is_none());
+    }
+
+    #[test]
+    fn test_override_symbol_of_another_unit() {
+        let mut registry = UnitRegistry::default();
+        registry
+            .load_str(
+                "unit meter_squared = meter^2 { symbol: m }",
+                ConflictPolicy::Override,
+            )
+            .unwrap();
+        assert_eq!(registry.get("m").unwrap().name(), "meter_squared");
+
+        let meter = registry.get("meter").unwrap();
+        assert_eq!(meter.symbol(), None);
+        assert_eq!(registry.get("metre").unwrap().name(), "meter");
+        assert_eq!(
+            registry.get_prefixed("kilometer").unwrap().to_base(1.0),
+            1.0e3
+        );
+        assert!(registry.get_prefixed("km").is_none());
+        assert_eq!(
+            registry
+                .units()
+                .filter(|unit| unit.name() == "meter")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_default_table() {
+        let parsed = parse_units_file(DEFAULT_UNITS).unwrap();
+        let registry = UnitRegistry::default();
+        let units = |registry: &UnitRegistry| -> HashMap<String, Unit> {
+            registry
+                .units()
+                .map(|unit| (unit.name().to_string(), unit.clone()))
+                .collect()
+        };
+        let aliases = |registry: &UnitRegistry| -> HashMap<String, String> {
+            registry
+                .aliases()
+                .map(|(alias, name)| (alias.to_string(), name.to_string()))
+                .collect()
+        };
+        assert_eq!(units(&registry), units(&parsed));
+        assert_eq!(aliases(&registry), aliases(&parsed));
+        for unit in parsed.units() {
+            assert!(
+                registry
+                    .accepted_prefix_sets(unit.name())
+                    .eq(parsed.accepted_prefix_sets(unit.name()))
+            );
+        }
+        assert_eq!(registry.prefix_sets(), parsed.prefix_sets());
+
+        assert_eq!(DEFAULT_TABLE.get("km"), None);
+        assert_eq!(DEFAULT_TABLE.get("°C").unwrap().name(), "degree_celsius");
+        assert_eq!(DEFAULT_TABLE.get("N").unwrap().to_base(1.0), 1.0);
+    }
+
+    #[test]
+    fn test_static_registry_changes() {
+        let mut registry = UnitRegistry::default();
+        assert_eq!(registry.units.len(), 0);
+
+        let meter = registry.remove("meter").unwrap();
+        assert_eq!(meter.name(), "meter");
+        assert!(registry.get("meter").is_none());
+        assert!(registry.get("m").is_none());
+        assert!(registry.get("metre").is_none());
+        assert!(registry.get_prefixed("km").is_none());
+        assert_eq!(registry.accepted_prefix_sets("meter").count(), 0);
+
+        registry.register(Unit::new_base("meter", LENGTH)).unwrap();
+        assert!(registry.get("m").is_none());
+        assert!(registry.get_prefixed("kilometer").is_none());
+        registry.allow_prefixes("meter", "standard").unwrap();
+        assert_eq!(
+            registry.get_prefixed("kilometer").unwrap().to_base(1.0),
+            1.0e3
+        );
+
+        assert_eq!(registry.remove_alias("ft"), Some("foot".into()));
+        assert!(registry.get("ft").is_none());
+        assert!(registry.get("foot").is_some());
+        registry.allow_prefixes("second", "binary").unwrap();
+        assert!(registry.get_prefixed("kibisecond").is_some());
+        assert!(registry.get_prefixed("ms").is_some());
+        assert!(DEFAULT_REGISTRY.get_prefixed("kibisecond").is_none());
+    }
+
+    #[test]
+    fn test_default_units_are_embedded() {
+        assert_eq!(DEFAULT_UNITS, fs::read_to_string("src/units.txt").unwrap());
+
+        let registry = UnitRegistry::default();
+        assert_eq!(
+            registry.units().count(),
+            UnitRegistry::new_from_file("src/units.txt")
+                .unwrap()
+                .units()
+                .count()
+        );
+        assert!(DEFAULT_REGISTRY.get_prefixed("kilometer").is_some());
+    }
+
     #[test]
     fn test_create_default() {
         let registry = UnitRegistry::new_from_file("src/units.txt").unwrap();
         assert!(registry.get("meter").is_some());
-        assert!(registry.get("kilometer").is_some());
+        assert!(registry.get_prefixed("kilometer").is_some());
         assert!(registry.get("degree_celsius").is_some());
         assert!(registry.get("decibel").is_some());
         assert!(registry.get("newton").is_some());
@@ -187,4 +1155,76 @@ mod tests {
         assert!(length_names.contains("kilometer"));
         assert!(!length_names.contains("degree_celsius"));
     }
+
+    #[test]
+    fn test_find_unit() {
+        let registry = UnitRegistry::default();
+        let force = MASS * LENGTH / TIME.pow(2);
+
+        assert_eq!(registry.find_unit(force, 1.0).unwrap().name(), "newton");
+        assert_eq!(registry.find_unit(MASS, 1.0).unwrap().name(), "kilogram");
+        assert_eq!(registry.find_unit(TIME, 3600.0).unwrap().name(), "hour");
+        assert_eq!(
+            registry.find_unit(force * LENGTH, 1.0e6).unwrap().name(),
+            "Megajoule"
+        );
+        assert_eq!(
+            registry.find_unit(TEMPERATURE, 1.0).unwrap().name(),
+            "degree_kelvin"
+        );
+        assert_eq!(registry.find_unit(force, 3.0), None);
+    }
+
+    #[test]
+    fn test_unprefixed() {
+        let registry = UnitRegistry::default();
+
+        let (unit, prefix) = registry.unprefixed("kilometer").unwrap();
+        assert_eq!(unit.name(), "meter");
+        assert_eq!(prefix.unwrap().name, "kilo");
+        assert_eq!(registry.unprefixed("hour").unwrap().1, None);
+        assert!(registry.unprefixed("kilohour").is_none());
+        assert!(
+            registry
+                .accepted_prefixes("byte")
+                .any(|prefix| prefix.name == "kibi")
+        );
+        assert_eq!(registry.accepted_prefixes("hour").count(), 0);
+
+        // `abc` is `a` + `bc` or `ab` + `c`: split as `get_prefixed` builds it
+        let mut registry = UnitRegistry::new();
+        registry
+            .load_str(
+                "prefix_set x { a = 2 ab = 3 }
+                 unit c { dimension: length transformation: identity prefixes: x }
+                 unit bc { dimension: length transformation: linear(scale: 5) prefixes: x }",
+                ConflictPolicy::Error,
+            )
+            .unwrap();
+        let (unit, prefix) = registry.unprefixed("abc").unwrap();
+        assert_eq!((unit.name(), prefix.unwrap().name.as_str()), ("bc", "a"));
+        assert_eq!(
+            registry.get_prefixed("abc").unwrap().to_base(1.0),
+            unit.with_prefix(prefix.unwrap()).to_base(1.0)
+        );
+    }
+
+    #[test]
+    fn test_parse_unit() {
+        let registry = UnitRegistry::new_from_file("src/units.txt").unwrap();
+
+        let force = registry.parse_unit("kilogram * meter / second^2").unwrap();
+        assert!(force.compatible(registry.get("newton").unwrap()));
+        assert_eq!(force.to_base(1.0), 1.0);
+
+        let pace = registry.parse_unit("(kilometer/hour)^-1").unwrap();
+        assert!((pace.to_base(1.0) - 3.6).abs() < 1.0e-9);
+
+        assert_eq!(
+            registry.parse_unit("meter / parrot"),
+            Err(Error::RegistryDoesNotContainUnit {
+                name: "parrot".into()
+            })
+        );
+    }
 }
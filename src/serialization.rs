@@ -0,0 +1,264 @@
+//! `serde` support for quantities (`serde` feature).
+//!
+//! Dimensions, units, transformations, prefix sets and registries derive `Serialize` and
+//! `Deserialize` structurally. Quantities serialize as their magnitude in their own unit and a
+//! unit expression, `{ "value": 5.0, "unit": "kilometer" }`, or with [`compact`] as a string,
+//! `"5 km"`. Deserializing accepts both forms and resolves the unit through the default
+//! registry, or through any registry with [`QuantitySeed`].
+
+use crate::formatting::{FormatSpec, FormatStyle};
+use crate::quantities::Quantity;
+use crate::registry::{DEFAULT_REGISTRY, UnitRegistry};
+use crate::units::Unit;
+use serde::de::{self, DeserializeSeed, MapAccess, Visitor};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// Serializes as `{ "value": 5.0, "unit": "kilometer" }`; derived units are written as
+/// expressions of the names of their factors (e.g. `kilogram*meter/second^2`), so any registry
+/// defining those units reads them back.
+impl Serialize for Quantity<f64> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let spec = FormatSpec::new(FormatStyle::Plain).with_long_names();
+        let mut state = serializer.serialize_struct("Quantity", 2)?;
+        state.serialize_field(
+            "value",
+            &self.m_as(self.unit()).map_err(serde::ser::Error::custom)?,
+        )?;
+        state.serialize_field("unit", &unit_expression(self.unit(), &spec))?;
+        state.end()
+    }
+}
+
+/// Unit of a serialized quantity: the formatted unit, or `1` for ratios of the same units (e.g.
+/// `meter / meter`), which format as nothing.
+fn unit_expression(unit: &Unit, spec: &FormatSpec) -> String {
+    match unit.format(spec) {
+        text if text.is_empty() => "1".into(),
+        text => text,
+    }
+}
+
+/// Deserializes `{ "value": 5.0, "unit": "km" }` or `"5 km"`, resolving the unit through the
+/// default registry.
+impl<'de> Deserialize<'de> for Quantity<f64> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        QuantitySeed::new(&DEFAULT_REGISTRY).deserialize(deserializer)
+    }
+}
+
+/// Deserializes a quantity resolving its unit through a registry.
+///
+/// # Examples
+/// `QuantitySeed::new(&registry).deserialize(&mut serde_json::Deserializer::from_str(json))`.
+#[derive(Clone, Copy)]
+pub struct QuantitySeed<'a> {
+    registry: &'a UnitRegistry,
+}
+
+impl<'a> QuantitySeed<'a> {
+    pub fn new(registry: &'a UnitRegistry) -> Self {
+        Self { registry }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for QuantitySeed<'_> {
+    type Value = Quantity<f64>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for QuantitySeed<'_> {
+    type Value = Quantity<f64>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a quantity string such as \"5 km\" or a map with value and unit")
+    }
+
+    fn visit_str<E: de::Error>(self, input: &str) -> Result<Self::Value, E> {
+        self.registry.parse_quantity(input).map_err(E::custom)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut value: Option<f64> = None;
+        let mut unit: Option<String> = None;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "value" if value.is_none() => value = Some(map.next_value()?),
+                "unit" if unit.is_none() => unit = Some(map.next_value()?),
+                "value" | "unit" => return Err(de::Error::duplicate_field("value or unit")),
+                _ => return Err(de::Error::unknown_field(&key, &["value", "unit"])),
+            }
+        }
+
+        let value = value.ok_or_else(|| de::Error::missing_field("value"))?;
+        let unit = unit.ok_or_else(|| de::Error::missing_field("unit"))?;
+        let unit = self.registry.parse_unit(&unit).map_err(de::Error::custom)?;
+
+        Ok(Quantity::new(value, unit))
+    }
+}
+
+/// Serializes quantities as compact strings (`"5 km"`, `"9.81 m/s^2"`) with the symbols of their
+/// units, for use with `#[serde(with = "arshin::serialization::compact")]`.
+pub mod compact {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        quantity: &Quantity<f64>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let value = quantity
+            .m_as(quantity.unit())
+            .map_err(serde::ser::Error::custom)?;
+        let unit = unit_expression(quantity.unit(), &FormatSpec::default());
+        serializer.collect_str(&format_args!("{} {}", value, unit))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Quantity<f64>, D::Error> {
+        Quantity::deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ArshinError as Error;
+    use crate::fundamentals::base::LENGTH;
+    use crate::registry::ConflictPolicy;
+    use crate::{q, u};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Measurement {
+        #[serde(with = "compact")]
+        distance: Quantity<f64>,
+        speed: Quantity<f64>,
+    }
+
+    #[test]
+    fn test_serialize_quantity() -> Result<(), Error> {
+        let distance = q!(5.0, "km")?;
+        assert_eq!(
+            serde_json::to_string(&distance).unwrap(),
+            r#"{"value":5.0,"unit":"kilometer"}"#
+        );
+
+        let measurement = Measurement {
+            distance,
+            speed: q!(3.0, "meter")? / q!(1.0, "second")?.pow(2),
+        };
+        assert_eq!(
+            serde_json::to_string(&measurement).unwrap(),
+            r#"{"distance":"5 km","speed":{"value":3.0,"unit":"meter/second^2"}}"#
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_quantity() -> Result<(), Error> {
+        let measurement: Measurement = serde_json::from_str(
+            r#"{"distance": "1.5 mi", "speed": {"unit": "km/hour", "value": 36}}"#,
+        )
+        .unwrap();
+        assert_eq!(measurement.distance.unit().name(), "mile");
+        assert!((measurement.speed.m_as(&u!("meter_per_second")?)? - 10.0).abs() < 1.0e-12);
+
+        let roundtrip: Quantity<f64> =
+            serde_json::from_str(&serde_json::to_string(&measurement.speed).unwrap()).unwrap();
+        assert_eq!(
+            roundtrip.base_magnitude(),
+            measurement.speed.base_magnitude()
+        );
+
+        assert!(serde_json::from_str::<Quantity<f64>>(r#""5 parrots""#).is_err());
+        assert!(serde_json::from_str::<Quantity<f64>>(r#"{"value": 5}"#).is_err());
+
+        let mut registry = UnitRegistry::new();
+        registry.register(Unit::new_linear("parrot", LENGTH, 0.3, 0.0))?;
+        let length = QuantitySeed::new(&registry)
+            .deserialize(&mut serde_json::Deserializer::from_str(r#""10 parrot""#))
+            .unwrap();
+        assert!((length.base_magnitude() - 3.0).abs() < 1.0e-12);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_roundtrip_custom_registry() -> Result<(), Error> {
+        let mut registry = UnitRegistry::new();
+        registry.load_str(
+            "unit parrot { dimension: length transformation: linear(scale: 0.3) prefixes: no symbol: pr }
+             unit tick { dimension: time transformation: linear(scale: 0.5) prefixes: no symbol: m }",
+            ConflictPolicy::Error,
+        )?;
+        let speed = registry.parse_quantity("4 parrot / tick^2")?;
+        let roundtrips = |json: &str| {
+            let quantity = QuantitySeed::new(&registry)
+                .deserialize(&mut serde_json::Deserializer::from_str(json))
+                .unwrap();
+            quantity.unit() == speed.unit() && quantity.base_magnitude() == speed.base_magnitude()
+        };
+
+        let json = serde_json::to_string(&speed).unwrap();
+        assert_eq!(json, r#"{"value":4.0,"unit":"parrot/tick^2"}"#);
+        assert!(roundtrips(&json));
+
+        let mut json = Vec::new();
+        compact::serialize(&speed, &mut serde_json::Serializer::new(&mut json)).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert_eq!(json, r#""4 pr/m^2""#);
+        assert!(roundtrips(&json));
+
+        let ratio = registry.parse_quantity("4 parrot")? / registry.parse_quantity("2 parrot")?;
+        let json = serde_json::to_string(&ratio).unwrap();
+        assert_eq!(json, r#"{"value":2.0,"unit":"1"}"#);
+        let mut compact_json = Vec::new();
+        compact::serialize(&ratio, &mut serde_json::Serializer::new(&mut compact_json)).unwrap();
+        assert_eq!(compact_json, br#""2 1""#);
+        for json in [json.as_bytes(), &compact_json] {
+            let quantity = QuantitySeed::new(&registry)
+                .deserialize(&mut serde_json::Deserializer::from_slice(json))
+                .unwrap();
+            assert_eq!(quantity.dimensionality(), ratio.dimensionality());
+            assert_eq!(quantity.base_magnitude(), ratio.base_magnitude());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_serialize_units() -> Result<(), Error> {
+        let newton = u!("newton")?;
+        let json = serde_json::to_string(&newton).unwrap();
+        assert_eq!(serde_json::from_str::<Unit>(&json).unwrap(), newton);
+
+        let speed = DEFAULT_REGISTRY.parse_unit("km/hour")?;
+        let json = serde_json::to_string(&speed).unwrap();
+        let roundtrip = serde_json::from_str::<Unit>(&json).unwrap();
+        assert_eq!(roundtrip, speed);
+        assert_eq!(roundtrip.reduced(), speed.reduced());
+
+        let celsius = u!("degree_celsius")?;
+        let json = serde_json::to_string(&celsius).unwrap();
+        assert_eq!(serde_json::from_str::<Unit>(&json).unwrap(), celsius);
+
+        let json = serde_json::to_string(&LENGTH).unwrap();
+        assert_eq!(json, "[0,1,0,0,0,0,0,0,0,0]");
+
+        let registry: UnitRegistry =
+            serde_json::from_str(&serde_json::to_string(&*DEFAULT_REGISTRY).unwrap()).unwrap();
+        assert_eq!(
+            registry.get_prefixed("km"),
+            DEFAULT_REGISTRY.get_prefixed("km")
+        );
+
+        Ok(())
+    }
+}
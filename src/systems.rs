@@ -0,0 +1,282 @@
+use crate::errors::ArshinError as Error;
+use crate::fundamentals::{Dimension, Fundamentals};
+use crate::registry::{DEFAULT_REGISTRY, UnitRegistry};
+use crate::units::Unit;
+use std::collections::HashMap;
+
+/// International System of Units: fundamental, base unit name.
+pub const SI_UNITS: [(Fundamentals, &str); 9] = [
+    (Fundamentals::Mass, "kilogram"),
+    (Fundamentals::Length, "meter"),
+    (Fundamentals::Time, "second"),
+    (Fundamentals::Current, "ampere"),
+    (Fundamentals::Temperature, "degree_kelvin"),
+    (Fundamentals::AmountOfSubstance, "mole"),
+    (Fundamentals::Luminosity, "candela"),
+    (Fundamentals::Angle, "radian"),
+    (Fundamentals::Bit, "bit"),
+];
+
+/// Centimeter-gram-second system, with the electromagnetic (EMU) current unit `biot`.
+pub const CGS_UNITS: [(Fundamentals, &str); 9] = [
+    (Fundamentals::Mass, "gram"),
+    (Fundamentals::Length, "centimeter"),
+    (Fundamentals::Time, "second"),
+    (Fundamentals::Current, "biot"),
+    (Fundamentals::Temperature, "degree_kelvin"),
+    (Fundamentals::AmountOfSubstance, "mole"),
+    (Fundamentals::Luminosity, "candela"),
+    (Fundamentals::Angle, "radian"),
+    (Fundamentals::Bit, "bit"),
+];
+
+/// Foot-pound-second system, with absolute temperatures in `degree_rankine`.
+pub const IMPERIAL_UNITS: [(Fundamentals, &str); 9] = [
+    (Fundamentals::Mass, "pound"),
+    (Fundamentals::Length, "foot"),
+    (Fundamentals::Time, "second"),
+    (Fundamentals::Current, "ampere"),
+    (Fundamentals::Temperature, "degree_rankine"),
+    (Fundamentals::AmountOfSubstance, "mole"),
+    (Fundamentals::Luminosity, "candela"),
+    (Fundamentals::Angle, "radian"),
+    (Fundamentals::Bit, "bit"),
+];
+
+/// A named system of units: a base unit for each fundamental dimension (e.g. `gram`,
+/// `centimeter` and `second` in CGS).
+///
+/// Quantities of any dimension are expressed in the system's coherent derived unit, the product
+/// of its base units (e.g. `((gram * centimeter) / [second]^2)` for a force in CGS). The `count`
+/// fundamental is dimensionless and needs no base unit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitSystem {
+    name: String,
+    base_units: HashMap<Fundamentals, Unit>,
+}
+
+impl UnitSystem {
+    /// Creates a system without base units.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            base_units: HashMap::new(),
+        }
+    }
+
+    /// SI system (`kilogram`, `meter`, `second`, `ampere`, ...), named `si`.
+    pub fn si() -> Self {
+        Self::from_table("si", &DEFAULT_REGISTRY, &SI_UNITS)
+            .expect("default units database contains the SI units")
+    }
+
+    /// CGS system (`gram`, `centimeter`, `second`, `biot`, ...), named `cgs`.
+    pub fn cgs() -> Self {
+        Self::from_table("cgs", &DEFAULT_REGISTRY, &CGS_UNITS)
+            .expect("default units database contains the CGS units")
+    }
+
+    /// Imperial system (`pound`, `foot`, `second`, `degree_rankine`, ...), named `imperial`.
+    pub fn imperial() -> Self {
+        Self::from_table("imperial", &DEFAULT_REGISTRY, &IMPERIAL_UNITS)
+            .expect("default units database contains the imperial units")
+    }
+
+    /// Creates a system from base unit names of a registry.
+    ///
+    /// # Errors
+    /// - `RegistryDoesNotContainUnit` if a unit is not in the registry.
+    /// - See [`UnitSystem::with_base_unit`].
+    pub fn from_table(
+        name: &str,
+        registry: &UnitRegistry,
+        table: &[(Fundamentals, &str)],
+    ) -> Result<Self, Error> {
+        table
+            .iter()
+            .try_fold(Self::new(name), |system, (fundamental, unit_name)| {
+                let unit =
+                    registry
+                        .get_prefixed(unit_name)
+                        .ok_or(Error::RegistryDoesNotContainUnit {
+                            name: unit_name.to_string(),
+                        })?;
+                system.with_base_unit(*fundamental, unit.into_owned())
+            })
+    }
+
+    /// Sets the base unit of a fundamental dimension.
+    ///
+    /// # Errors
+    /// - `UnitsConversionError` if the unit does not have the dimension of `fundamental`.
+    /// - `InvalidBaseUnit` if the unit has an offset, logarithmic or custom transformation.
+    pub fn with_base_unit(mut self, fundamental: Fundamentals, unit: Unit) -> Result<Self, Error> {
+        let dimension = Dimension::new_from_fundamental(fundamental);
+        if *unit.dimensionality() != dimension {
+            return Err(Error::UnitsConversionError {
+                expected: dimension,
+                got: *unit.dimensionality(),
+            });
+        }
+        if unit.scale().is_none() {
+            return Err(Error::InvalidBaseUnit {
+                system: self.name,
+                name: unit.name().into(),
+            });
+        }
+
+        self.base_units.insert(fundamental, unit);
+        Ok(self)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn base_unit(&self, fundamental: Fundamentals) -> Option<&Unit> {
+        self.base_units.get(&fundamental)
+    }
+
+    /// Base units of the system with their fundamentals, in no particular order.
+    pub fn base_units(&self) -> impl Iterator<Item = (Fundamentals, &Unit)> {
+        self.base_units
+            .iter()
+            .map(|(fundamental, unit)| (*fundamental, unit))
+    }
+
+    /// Coherent unit of the system for a dimension: the product of the base units raised to the
+    /// powers of the dimension (e.g. `((pound * foot) / [second]^2)` for a force in imperial).
+    ///
+    /// # Errors
+    /// `MissingBaseUnit` if the system has no base unit for a fundamental of the dimension.
+    pub fn coherent_unit(&self, dimension: Dimension) -> Result<Unit, Error> {
+        let mut factors = Vec::new();
+        for fundamental in Fundamentals::iter().filter(|f| *f != Fundamentals::Count) {
+            let power = dimension.powers()[fundamental.to_index()];
+            if power != 0 {
+                let unit = self
+                    .base_unit(fundamental)
+                    .ok_or_else(|| Error::MissingBaseUnit {
+                        system: self.name.clone(),
+                        fundamental,
+                    })?;
+                factors.push((unit, power as i64));
+            }
+        }
+
+        if let [(unit, 1)] = factors.as_slice()
+            && *unit.dimensionality() == dimension
+        {
+            return Ok((*unit).clone());
+        }
+
+        let mut product: Option<Unit> = None;
+        for (unit, power) in factors {
+            let factor = unit.checked_pow(power)?;
+            product = Some(match product {
+                Some(product) => product.checked_mul(&factor)?,
+                None => factor,
+            });
+        }
+
+        Ok(match product.map(|unit| unit.reduced()) {
+            // The count power of the dimension has no base unit
+            Some(unit) => unit.with_dimensionality(dimension),
+            None => Unit::new_base("1", dimension),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fundamentals::base::{LENGTH, MASS, TIME};
+    use crate::{q, u};
+
+    #[test]
+    fn test_default_systems() {
+        let si = UnitSystem::si();
+        assert_eq!(si.name(), "si");
+        assert_eq!(si.base_unit(Fundamentals::Mass).unwrap().name(), "kilogram");
+        assert_eq!(
+            UnitSystem::cgs()
+                .base_unit(Fundamentals::Current)
+                .unwrap()
+                .name(),
+            "biot"
+        );
+        assert_eq!(UnitSystem::imperial().base_units().count(), 9);
+
+        let force = UnitSystem::cgs()
+            .coherent_unit(MASS * LENGTH / TIME.pow(2))
+            .unwrap();
+        assert_eq!(force.name(), "((gram * centimeter) / [second]^2)");
+        assert_eq!(force.to_base(1.0), 1.0e-5);
+        assert_eq!(si.coherent_unit(LENGTH).unwrap().symbol(), Some("m"));
+    }
+
+    #[test]
+    fn test_to_system() -> Result<(), Error> {
+        let close = |a: f64, b: f64| (a - b).abs() < 1.0e-9 * b.abs();
+
+        let force = q!(1.0, "newton")?.to_system(&UnitSystem::cgs())?;
+        assert!(close(force.m_as(&u!("dyne")?)?, 1.0e5));
+        assert_eq!(force.simplify(&DEFAULT_REGISTRY).unit().name(), "dyne");
+
+        let voltage = q!(1.0, "volt")?.to_system(&UnitSystem::cgs())?;
+        assert_eq!(voltage.simplify(&DEFAULT_REGISTRY).unit().name(), "abvolt");
+
+        let speed = q!(1.0, "meter_per_second")?.to_system(&UnitSystem::imperial())?;
+        assert_eq!(speed.unit().name(), "(foot / second)");
+        assert!(close(speed.m_as(&u!("mph")?)?, 3600.0 / 1609.344));
+        assert!(close(speed.m_as(speed.unit())?, 1.0 / 0.3048));
+
+        let temperature = q!(20.0, "degree_celsius")?.to_system(&UnitSystem::si())?;
+        assert_eq!(temperature.unit().name(), "degree_kelvin");
+        assert!(close(temperature.m_as(&u!("K")?)?, 293.15));
+
+        let energy = q!(1.0, "calorie")?.to_system(&UnitSystem::si())?;
+        assert_eq!(energy.simplify(&DEFAULT_REGISTRY).unit().name(), "joule");
+
+        let ratio = q!(50.0, "percent")?.to_system(&UnitSystem::imperial())?;
+        assert_eq!(ratio.unit().name(), "1");
+        assert_eq!(ratio.base_magnitude(), 0.5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_system_errors() -> Result<(), Error> {
+        let lengths = UnitSystem::new("lengths").with_base_unit(Fundamentals::Length, u!("km")?)?;
+        assert_eq!(
+            q!(1.0, "hour")?.to_system(&lengths).err(),
+            Some(Error::MissingBaseUnit {
+                system: "lengths".into(),
+                fundamental: Fundamentals::Time,
+            })
+        );
+
+        assert_eq!(
+            lengths
+                .clone()
+                .with_base_unit(Fundamentals::Time, u!("meter")?),
+            Err(Error::UnitsConversionError {
+                expected: TIME,
+                got: LENGTH,
+            })
+        );
+        assert_eq!(
+            lengths.with_base_unit(Fundamentals::Temperature, u!("degree_celsius")?),
+            Err(Error::InvalidBaseUnit {
+                system: "lengths".into(),
+                name: "degree_celsius".into(),
+            })
+        );
+        assert!(matches!(
+            UnitSystem::from_table("bad", &DEFAULT_REGISTRY, &[(Fundamentals::Mass, "slug")]),
+            Err(Error::RegistryDoesNotContainUnit { .. })
+        ));
+
+        Ok(())
+    }
+}
@@ -1,5 +1,7 @@
+use crate::errors::ArshinError as Error;
 use std::fmt::Debug;
 use std::ops::{Add, Div, Mul, Sub};
+use std::sync::Arc;
 
 /// Trait for mathematical operations on f64-like types, used in transformations.
 pub trait MathOpsF64:
@@ -14,11 +16,25 @@ pub trait MathOpsF64:
     fn log(&self, base: f64) -> Self;
     fn exp(&self, base: f64) -> Self;
     fn pow(&self, power: f64) -> Self;
+
+    /// Applies a scalar function to the value, e.g. a [`UnitConversion`] of a unit with a custom
+    /// transformation.
+    fn apply(&self, function: &dyn Fn(f64) -> f64) -> Self;
 }
 
 impl MathOpsF64 for f64 {
     fn log(&self, base: f64) -> Self {
-        f64::log(*self, base)
+        // The dedicated logarithms are exact where `ln(x) / ln(base)` rounds, e.g. at 1000 in
+        // base 10, so values of logarithmic units survive a round trip through their base unit
+        if base == 10.0 {
+            self.log10()
+        } else if base == 2.0 {
+            self.log2()
+        } else if base == std::f64::consts::E {
+            self.ln()
+        } else {
+            f64::log(*self, base)
+        }
     }
 
     fn exp(&self, base: f64) -> Self {
@@ -28,17 +44,76 @@ impl MathOpsF64 for f64 {
     fn pow(&self, power: f64) -> Self {
         self.powf(power)
     }
+
+    fn apply(&self, function: &dyn Fn(f64) -> f64) -> Self {
+        function(*self)
+    }
+}
+
+/// User-defined conversion of a nonlinear unit (e.g. wire gauges, calibration curves) to and
+/// from its base unit, used by [`UnitTransformation::Custom`].
+///
+/// The conversion should be monotonic, with `from_base` inverting `to_base`;
+/// [`UnitConversion::check_inverse`] verifies this on sample values.
+pub trait UnitConversion: Debug + Send + Sync {
+    /// Converts a value in the unit to base units.
+    fn to_base(&self, value: f64) -> f64;
+
+    /// Converts a value in base units to the unit.
+    fn from_base(&self, value: f64) -> f64;
+
+    /// Checks that `from_base` inverts `to_base` on the given values.
+    ///
+    /// # Parameters
+    /// - `values`: Sample values in the unit.
+    /// - `tolerance`: Allowed error, relative to the value (absolute below 1).
+    ///
+    /// # Errors
+    /// `NonInvertibleTransformation` with the first value that does not convert back.
+    fn check_inverse(&self, values: &[f64], tolerance: f64) -> Result<(), Error> {
+        for &value in values {
+            let result = self.from_base(self.to_base(value));
+            let error = (result - value).abs();
+            if error.is_nan() || error > tolerance * value.abs().max(1.0) {
+                return Err(Error::NonInvertibleTransformation { value, result });
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Enum for unit conversion transformations.
-#[derive(Debug, PartialEq, Copy, Clone)]
+///
+/// Custom transformations compare equal only to themselves (the same `Arc`).
+///
+/// With the `serde` feature, custom transformations can be neither serialized nor deserialized.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UnitTransformation {
     Identity,
     Linear(LinearTransformation),
-    Decibel(DecibelTransformation),
+    Logarithmic(LogarithmicTransformation),
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(Arc<dyn UnitConversion>),
+}
+
+impl PartialEq for UnitTransformation {
+    fn eq(&self, other: &Self) -> bool {
+        use UnitTransformation::*;
+
+        match (self, other) {
+            (Identity, Identity) => true,
+            (Linear(a), Linear(b)) => a == b,
+            (Logarithmic(a), Logarithmic(b)) => a == b,
+            (Custom(a), Custom(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LinearTransformation {
     pub scale: f64,  // scale factor
     pub offset: f64, // shift factor (bias)
@@ -56,7 +131,10 @@ impl UnitTransformation {
         match self {
             UnitTransformation::Identity => value,
             UnitTransformation::Linear(trans) => trans.to_base(value),
-            UnitTransformation::Decibel(trans) => trans.to_base(value),
+            UnitTransformation::Logarithmic(trans) => trans.to_base(value),
+            UnitTransformation::Custom(conversion) => {
+                value.apply(&|value| conversion.to_base(value))
+            }
         }
     }
 
@@ -71,7 +149,10 @@ impl UnitTransformation {
         match self {
             UnitTransformation::Identity => value,
             UnitTransformation::Linear(trans) => trans.from_base(value),
-            UnitTransformation::Decibel(trans) => trans.from_base(value),
+            UnitTransformation::Logarithmic(trans) => trans.from_base(value),
+            UnitTransformation::Custom(conversion) => {
+                value.apply(&|value| conversion.from_base(value))
+            }
         }
     }
 }
@@ -86,7 +167,7 @@ impl LinearTransformation {
     ///
     /// # Examples
     /// Celsius to Kelvin: scale=1.0, offset=273.15.
-    pub fn new(scale: f64, offset: f64) -> Self {
+    pub const fn new(scale: f64, offset: f64) -> Self {
         Self { scale, offset }
     }
 
@@ -109,12 +190,117 @@ impl LinearTransformation {
     }
 }
 
-/// Decibel transformation (10 * log10(value / p0)).
+/// Logarithmic transformation (factor * log_base(value / reference)).
+///
+/// Covers decibels (base 10, factor 10 for power and 20 for root-power quantities), bels,
+/// nepers, octaves, decades, pH (factor -1) and astronomical magnitudes (factor -2.5).
+///
+/// Dimensionless units with the `gain` flag measure gains (power ratios such as dB, bel or Np),
+/// which shift levels in additions; other dimensionless units (octaves, decades) only add to
+/// each other, even with the same transformation.
+#[derive(Debug, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogarithmicTransformation {
+    pub base: f64,      // base of the logarithm
+    pub factor: f64,    // multiplier of the logarithm
+    pub reference: f64, // base value of the relation
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gain: bool, // measures power ratios
+}
+
+impl LogarithmicTransformation {
+    /// Creates a new logarithmic transformation.
+    ///
+    /// # Parameters
+    /// - `base`: Base of the logarithm (e.g. 10, `std::f64::consts::E`, 2).
+    /// - `factor`: Multiplier of the logarithm (e.g. 10 for decibels).
+    /// - `reference`: Reference value (e.g., 1.0 for relative units, 1e-3 W for dBm).
+    pub const fn new(base: f64, factor: f64, reference: f64) -> Self {
+        Self {
+            base,
+            factor,
+            reference,
+            gain: false,
+        }
+    }
+
+    /// Creates the transformation of a gain unit, a power ratio with reference 1 (e.g.
+    /// `new_gain(10.0, 10.0)` for decibels, `new_gain(std::f64::consts::E, 0.5)` for nepers).
+    pub const fn new_gain(base: f64, factor: f64) -> Self {
+        Self {
+            gain: true,
+            ..Self::new(base, factor, 1.0)
+        }
+    }
+
+    /// Creates a decibel transformation of a power quantity (10 * log10(value / p0)).
+    pub const fn decibel(p0: f64) -> Self {
+        Self::new(10.0, 10.0, p0)
+    }
+
+    /// Creates a decibel transformation of a root-power quantity (20 * log10(value / p0)).
+    pub const fn decibel_root_power(p0: f64) -> Self {
+        Self::new(10.0, 20.0, p0)
+    }
+
+    pub fn base(&self) -> f64 {
+        self.base
+    }
+
+    pub fn factor(&self) -> f64 {
+        self.factor
+    }
+
+    pub fn reference(&self) -> f64 {
+        self.reference
+    }
+
+    /// Checks if the transformation is of a gain unit (see [`LogarithmicTransformation::new_gain`]).
+    pub fn is_gain(&self) -> bool {
+        self.gain
+    }
+
+    /// Exponent relating the quantity to power: 2 for root-power (field) quantities such as
+    /// voltage or sound pressure, 1 otherwise.
+    ///
+    /// Root-power levels follow the usual conventions: factor 20 for base 10 (dBV, dB SPL) and
+    /// factor 1 for base e (nepers of amplitude).
+    pub fn power_exponent(&self) -> f64 {
+        let root_power = if self.base == std::f64::consts::E {
+            1.0
+        } else if self.base == 10.0 {
+            20.0
+        } else {
+            return 1.0;
+        };
+
+        if self.factor.abs() == root_power {
+            2.0
+        } else {
+            1.0
+        }
+    }
+
+    /// Converts to linear: reference * base^(value / factor).
+    fn to_base<T: MathOpsF64>(&self, value: T) -> T {
+        (value / self.factor).exp(self.base) * self.reference
+    }
+
+    /// Converts from linear: factor * log_base(value / reference).
+    fn from_base<T: MathOpsF64>(&self, value: T) -> T {
+        (value / self.reference).log(self.base) * self.factor
+    }
+}
+
+/// Decibel transformation (10 * log10(value / p0)) of 0.2, replaced by
+/// [`LogarithmicTransformation::decibel`].
+#[deprecated(since = "0.3.0", note = "use `LogarithmicTransformation::decibel`")]
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub struct DecibelTransformation {
     pub p0: f64, // base value of the relation
 }
 
+#[allow(deprecated)]
 impl DecibelTransformation {
     /// Creates a new decibel transformation.
     ///
@@ -127,15 +313,12 @@ impl DecibelTransformation {
     pub fn p0(&self) -> f64 {
         self.p0
     }
+}
 
-    /// Converts dB to linear: 10^(value / 10) * p0.
-    fn to_base<T: MathOpsF64>(&self, value: T) -> T {
-        (value / 10.0f64).exp(10.0f64) * self.p0
-    }
-
-    /// Converts linear to dB: 10 * log10(value / p0).
-    fn from_base<T: MathOpsF64>(&self, value: T) -> T {
-        (value / self.p0).log(10.0) * 10.0f64
+#[allow(deprecated)]
+impl From<DecibelTransformation> for LogarithmicTransformation {
+    fn from(transformation: DecibelTransformation) -> Self {
+        LogarithmicTransformation::decibel(transformation.p0)
     }
 }
 
@@ -167,8 +350,8 @@ mod transformations_tests {
 
     #[test]
     fn test_decibel_transformation() {
-        let trans = DecibelTransformation::new(1.0);
-        assert_eq!(trans.p0(), 1.0);
+        let trans = LogarithmicTransformation::decibel(1.0);
+        assert_eq!(trans.reference(), 1.0);
 
         // Test to_base: 10 ^ (value / 10.0) * p0
         assert_eq!(trans.to_base(0.0), 1.0); // 10^(0/10) * 1 = 1
@@ -177,6 +360,144 @@ mod transformations_tests {
         // Test from_base: 10.0 * log10(value / p0)
         assert_eq!(trans.from_base(1.0), 0.0); // log10(1/1) * 10 = 0
         assert_eq!(trans.from_base(10.0), 10.0); // log10(10/1) * 10 = 10
+
+        // Root-power: 20 * log10(value / p0)
+        let trans = LogarithmicTransformation::decibel_root_power(1.0);
+        assert_eq!(trans.factor(), 20.0);
+        assert_eq!(trans.power_exponent(), 2.0);
+        assert_eq!(trans.to_base(20.0), 10.0);
+        assert_eq!(trans.from_base(100.0), 40.0);
+
+        // The transformation of 0.2 converts to the logarithmic one
+        #[allow(deprecated)]
+        let trans = LogarithmicTransformation::from(DecibelTransformation::new(1.0e-3));
+        assert_eq!(trans, LogarithmicTransformation::decibel(1.0e-3));
+    }
+
+    #[test]
+    fn test_logarithmic_transformation() {
+        // Octaves: log2(value)
+        let octave = LogarithmicTransformation::new(2.0, 1.0, 1.0);
+        assert_eq!(octave.to_base(3.0), 8.0);
+        assert_eq!(octave.from_base(0.5), -1.0);
+        assert_eq!(octave.power_exponent(), 1.0);
+
+        // pH: -log10(activity / 1 mol/L), in mol/m^3
+        let ph = LogarithmicTransformation::new(10.0, -1.0, 1000.0);
+        assert!((ph.to_base(7.0) - 1.0e-4).abs() < 1.0e-15);
+        assert!((ph.from_base(1.0e-4) - 7.0).abs() < 1.0e-12);
+
+        // Nepers: 1 Np of power is e^2
+        let neper = LogarithmicTransformation::new(std::f64::consts::E, 0.5, 1.0);
+        assert!((neper.to_base(1.0) - std::f64::consts::E.powi(2)).abs() < 1.0e-12);
+        assert!(!neper.is_gain());
+
+        // Gains are ratios of powers, so their reference is 1
+        let neper = LogarithmicTransformation::new_gain(std::f64::consts::E, 0.5);
+        assert!(neper.is_gain());
+        assert_eq!(neper.reference(), 1.0);
+    }
+
+    #[derive(Debug)]
+    struct Square;
+
+    impl UnitConversion for Square {
+        fn to_base(&self, value: f64) -> f64 {
+            value * value
+        }
+
+        fn from_base(&self, value: f64) -> f64 {
+            value.sqrt()
+        }
+    }
+
+    #[test]
+    fn test_custom_transformation() {
+        let square: Arc<dyn UnitConversion> = Arc::new(Square);
+        let trans = UnitTransformation::Custom(square.clone());
+        assert_eq!(trans.to_base(3.0), 9.0);
+        assert_eq!(trans.from_base(16.0), 4.0);
+
+        assert_eq!(trans, UnitTransformation::Custom(square.clone()));
+        assert_ne!(trans, UnitTransformation::Custom(Arc::new(Square)));
+        assert_ne!(trans, UnitTransformation::Identity);
+
+        assert_eq!(
+            square.check_inverse(&[0.0, 0.5, 3.0, 1.0e6], 1.0e-12),
+            Ok(())
+        );
+        assert_eq!(
+            square.check_inverse(&[2.0, -2.0], 1.0e-12),
+            Err(Error::NonInvertibleTransformation {
+                value: -2.0,
+                result: 2.0
+            })
+        );
+    }
+
+    /// Magnitude type implementing `MathOpsF64` like a user type would.
+    #[derive(Debug, PartialEq, Copy, Clone)]
+    struct Magnitude(f64);
+
+    impl Add<f64> for Magnitude {
+        type Output = Self;
+
+        fn add(self, rhs: f64) -> Self {
+            Self(self.0 + rhs)
+        }
+    }
+
+    impl Sub<f64> for Magnitude {
+        type Output = Self;
+
+        fn sub(self, rhs: f64) -> Self {
+            Self(self.0 - rhs)
+        }
+    }
+
+    impl Mul<f64> for Magnitude {
+        type Output = Self;
+
+        fn mul(self, rhs: f64) -> Self {
+            Self(self.0 * rhs)
+        }
+    }
+
+    impl Div<f64> for Magnitude {
+        type Output = Self;
+
+        fn div(self, rhs: f64) -> Self {
+            Self(self.0 / rhs)
+        }
+    }
+
+    impl MathOpsF64 for Magnitude {
+        fn log(&self, base: f64) -> Self {
+            Self(self.0.log(base))
+        }
+
+        fn exp(&self, base: f64) -> Self {
+            Self(base.powf(self.0))
+        }
+
+        fn pow(&self, power: f64) -> Self {
+            Self(self.0.powf(power))
+        }
+
+        fn apply(&self, function: &dyn Fn(f64) -> f64) -> Self {
+            Self(function(self.0))
+        }
+    }
+
+    #[test]
+    fn test_math_ops_user_type() {
+        let linear = UnitTransformation::Linear(LinearTransformation::new(2.0, 1.0));
+        assert_eq!(linear.to_base(Magnitude(3.0)), Magnitude(7.0));
+        let decibel = UnitTransformation::Logarithmic(LogarithmicTransformation::decibel(1.0));
+        assert_eq!(decibel.from_base(Magnitude(100.0)), Magnitude(20.0));
+        let square = UnitTransformation::Custom(Arc::new(Square));
+        assert_eq!(square.to_base(Magnitude(3.0)), Magnitude(9.0));
+        assert_eq!(square.from_base(Magnitude(9.0)), Magnitude(3.0));
     }
 
     #[test]
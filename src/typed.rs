@@ -0,0 +1,400 @@
+use crate::errors::ArshinError as Error;
+use crate::fundamentals::Dimension;
+use crate::quantities::Quantity;
+use crate::systems::UnitSystem;
+use crate::transformations::MathOpsF64;
+use crate::units::Unit;
+use std::marker::PhantomData;
+use std::ops::{Add, Div, Mul, Sub};
+use typenum::{Diff, Integer, Sum};
+
+/// Dimension as a type: exponents of mass, length, time, current, temperature, amount of
+/// substance, luminosity, angle and bit (see [`dim`] for common dimensions).
+///
+/// Stable Rust cannot do arithmetic on const generic parameters, so the exponents are `typenum`
+/// integers (`Z0`, `P1`, `N2`, ...); this lets `Mul`/`Div` compute the resulting dimension at
+/// compile time.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Dim<M, L, T, I, Th, N, J, A, B>(Exponents<M, L, T, I, Th, N, J, A, B>);
+
+/// Marker of the exponents of a [`Dim`].
+type Exponents<M, L, T, I, Th, N, J, A, B> = PhantomData<(M, L, T, I, Th, N, J, A, B)>;
+
+/// Type-level dimension with its runtime counterpart.
+pub trait Dimensions {
+    const DIMENSION: Dimension;
+}
+
+impl<M, L, T, I, Th, N, J, A, B> Dimensions for Dim<M, L, T, I, Th, N, J, A, B>
+where
+    M: Integer,
+    L: Integer,
+    T: Integer,
+    I: Integer,
+    Th: Integer,
+    N: Integer,
+    J: Integer,
+    A: Integer,
+    B: Integer,
+{
+    const DIMENSION: Dimension = Dimension::new([
+        M::I32,
+        L::I32,
+        T::I32,
+        I::I32,
+        Th::I32,
+        N::I32,
+        J::I32,
+        A::I32,
+        B::I32,
+        0,
+    ]);
+}
+
+// Dimension multiplication/division adds/subtracts the exponents.
+macro_rules! dimension_operation {
+    ($operation:ident, $method:ident, $exponent_operation:ident, $exponent_result:ident) => {
+        impl<M1, L1, T1, I1, Th1, N1, J1, A1, B1, M2, L2, T2, I2, Th2, N2, J2, A2, B2>
+            $operation<Dim<M2, L2, T2, I2, Th2, N2, J2, A2, B2>>
+            for Dim<M1, L1, T1, I1, Th1, N1, J1, A1, B1>
+        where
+            M1: $exponent_operation<M2>,
+            L1: $exponent_operation<L2>,
+            T1: $exponent_operation<T2>,
+            I1: $exponent_operation<I2>,
+            Th1: $exponent_operation<Th2>,
+            N1: $exponent_operation<N2>,
+            J1: $exponent_operation<J2>,
+            A1: $exponent_operation<A2>,
+            B1: $exponent_operation<B2>,
+        {
+            type Output = Dim<
+                $exponent_result<M1, M2>,
+                $exponent_result<L1, L2>,
+                $exponent_result<T1, T2>,
+                $exponent_result<I1, I2>,
+                $exponent_result<Th1, Th2>,
+                $exponent_result<N1, N2>,
+                $exponent_result<J1, J2>,
+                $exponent_result<A1, A2>,
+                $exponent_result<B1, B2>,
+            >;
+
+            fn $method(self, _: Dim<M2, L2, T2, I2, Th2, N2, J2, A2, B2>) -> Self::Output {
+                Dim(PhantomData)
+            }
+        }
+    };
+}
+
+dimension_operation!(Mul, mul, Add, Sum);
+dimension_operation!(Div, div, Sub, Diff);
+
+/// Common dimensions.
+pub mod dim {
+    use super::Dim;
+    use typenum::{N1, N2, N3, P1, P2, P3, Z0};
+
+    pub type Dimensionless = Dim<Z0, Z0, Z0, Z0, Z0, Z0, Z0, Z0, Z0>;
+    pub type Mass = Dim<P1, Z0, Z0, Z0, Z0, Z0, Z0, Z0, Z0>;
+    pub type Length = Dim<Z0, P1, Z0, Z0, Z0, Z0, Z0, Z0, Z0>;
+    pub type Time = Dim<Z0, Z0, P1, Z0, Z0, Z0, Z0, Z0, Z0>;
+    pub type Current = Dim<Z0, Z0, Z0, P1, Z0, Z0, Z0, Z0, Z0>;
+    pub type Temperature = Dim<Z0, Z0, Z0, Z0, P1, Z0, Z0, Z0, Z0>;
+    pub type AmountOfSubstance = Dim<Z0, Z0, Z0, Z0, Z0, P1, Z0, Z0, Z0>;
+    pub type Luminosity = Dim<Z0, Z0, Z0, Z0, Z0, Z0, P1, Z0, Z0>;
+    pub type Angle = Dim<Z0, Z0, Z0, Z0, Z0, Z0, Z0, P1, Z0>;
+    pub type Information = Dim<Z0, Z0, Z0, Z0, Z0, Z0, Z0, Z0, P1>;
+
+    pub type Area = Dim<Z0, P2, Z0, Z0, Z0, Z0, Z0, Z0, Z0>;
+    pub type Volume = Dim<Z0, P3, Z0, Z0, Z0, Z0, Z0, Z0, Z0>;
+    pub type Frequency = Dim<Z0, Z0, N1, Z0, Z0, Z0, Z0, Z0, Z0>;
+    pub type Velocity = Dim<Z0, P1, N1, Z0, Z0, Z0, Z0, Z0, Z0>;
+    pub type Acceleration = Dim<Z0, P1, N2, Z0, Z0, Z0, Z0, Z0, Z0>;
+    pub type Force = Dim<P1, P1, N2, Z0, Z0, Z0, Z0, Z0, Z0>;
+    pub type Pressure = Dim<P1, N1, N2, Z0, Z0, Z0, Z0, Z0, Z0>;
+    pub type Energy = Dim<P1, P2, N2, Z0, Z0, Z0, Z0, Z0, Z0>;
+    pub type Power = Dim<P1, P2, N3, Z0, Z0, Z0, Z0, Z0, Z0>;
+}
+
+/// Quantity with a dimension checked at compile time: magnitude in base units + dimension type.
+///
+/// Same-dimension quantities can be added and subtracted, and products and quotients get their
+/// dimension computed at compile time. Converts losslessly to and from [`Quantity`].
+///
+/// # Examples
+///
+/// ```
+/// use arshin::typed::{Length, Time, Velocity};
+/// let distance = Length::from_base(100.0);
+/// let time = Time::from_base(9.58);
+/// let speed: Velocity = distance / time;
+/// assert!((speed.base_magnitude() - 10.44).abs() < 1.0e-2);
+/// ```
+///
+/// Mismatched dimensions do not compile:
+///
+/// ```compile_fail
+/// use arshin::typed::{Length, Time};
+/// let _ = Length::from_base(1.0) + Time::from_base(1.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Q<D, T = f64> {
+    magnitude: T,
+    dimension: PhantomData<D>,
+}
+
+pub type Dimensionless<T = f64> = Q<dim::Dimensionless, T>;
+pub type Mass<T = f64> = Q<dim::Mass, T>;
+pub type Length<T = f64> = Q<dim::Length, T>;
+pub type Time<T = f64> = Q<dim::Time, T>;
+pub type Current<T = f64> = Q<dim::Current, T>;
+pub type Temperature<T = f64> = Q<dim::Temperature, T>;
+pub type AmountOfSubstance<T = f64> = Q<dim::AmountOfSubstance, T>;
+pub type Luminosity<T = f64> = Q<dim::Luminosity, T>;
+pub type Angle<T = f64> = Q<dim::Angle, T>;
+pub type Information<T = f64> = Q<dim::Information, T>;
+pub type Area<T = f64> = Q<dim::Area, T>;
+pub type Volume<T = f64> = Q<dim::Volume, T>;
+pub type Frequency<T = f64> = Q<dim::Frequency, T>;
+pub type Velocity<T = f64> = Q<dim::Velocity, T>;
+pub type Acceleration<T = f64> = Q<dim::Acceleration, T>;
+pub type Force<T = f64> = Q<dim::Force, T>;
+pub type Pressure<T = f64> = Q<dim::Pressure, T>;
+pub type Energy<T = f64> = Q<dim::Energy, T>;
+pub type Power<T = f64> = Q<dim::Power, T>;
+
+impl<D, T> Q<D, T>
+where
+    D: Dimensions,
+    T: MathOpsF64,
+{
+    /// Creates a quantity from a magnitude in base units.
+    pub fn from_base(magnitude: T) -> Self {
+        Self {
+            magnitude,
+            dimension: PhantomData,
+        }
+    }
+
+    /// Creates a quantity from a magnitude in `unit`.
+    ///
+    /// # Errors
+    /// If the dimension of `unit` is not `D`.
+    pub fn new(magnitude: T, unit: &Unit) -> Result<Self, Error> {
+        Self::check_dimension(unit.dimensionality())?;
+        Ok(Self::from_base(unit.to_base(magnitude)))
+    }
+
+    /// Gets magnitude in a target unit.
+    ///
+    /// # Errors
+    /// If the dimension of `unit` is not `D`.
+    pub fn magnitude_as(&self, unit: &Unit) -> Result<T, Error> {
+        Self::check_dimension(unit.dimensionality())?;
+        Ok(unit.from_base(self.magnitude))
+    }
+
+    /// Shorthand for `magnitude_as`.
+    pub fn m_as(&self, unit: &Unit) -> Result<T, Error> {
+        self.magnitude_as(unit)
+    }
+
+    pub fn base_magnitude(&self) -> T {
+        self.magnitude
+    }
+
+    pub fn dimensionality(&self) -> Dimension {
+        D::DIMENSION
+    }
+
+    fn check_dimension(dimension: &Dimension) -> Result<(), Error> {
+        if *dimension != D::DIMENSION {
+            return Err(Error::UnitsConversionError {
+                expected: D::DIMENSION,
+                got: *dimension,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Converts to a dynamic quantity in the coherent SI unit of `D` (e.g. `meter / second`).
+impl<D, T> From<Q<D, T>> for Quantity<T>
+where
+    D: Dimensions,
+    T: MathOpsF64,
+{
+    fn from(quantity: Q<D, T>) -> Self {
+        let unit = UnitSystem::si()
+            .coherent_unit(D::DIMENSION)
+            .expect("SI has a base unit of every fundamental");
+        Quantity::new(unit.from_base(quantity.magnitude), unit)
+    }
+}
+
+/// Converts from a dynamic quantity, checking its dimension.
+impl<D, T> TryFrom<Quantity<T>> for Q<D, T>
+where
+    D: Dimensions,
+    T: MathOpsF64,
+{
+    type Error = Error;
+
+    fn try_from(quantity: Quantity<T>) -> Result<Self, Self::Error> {
+        Self::check_dimension(quantity.dimensionality())?;
+        Ok(Self::from_base(quantity.base_magnitude()))
+    }
+}
+
+impl<D, T> Add for Q<D, T>
+where
+    T: Add<Output = T>,
+{
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self {
+            magnitude: self.magnitude + other.magnitude,
+            dimension: PhantomData,
+        }
+    }
+}
+
+impl<D, T> Sub for Q<D, T>
+where
+    T: Sub<Output = T>,
+{
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Self {
+            magnitude: self.magnitude - other.magnitude,
+            dimension: PhantomData,
+        }
+    }
+}
+
+impl<D1, D2, T> Mul<Q<D2, T>> for Q<D1, T>
+where
+    D1: Mul<D2>,
+    T: Mul<Output = T>,
+{
+    type Output = Q<<D1 as Mul<D2>>::Output, T>;
+
+    fn mul(self, other: Q<D2, T>) -> Self::Output {
+        Q {
+            magnitude: self.magnitude * other.magnitude,
+            dimension: PhantomData,
+        }
+    }
+}
+
+impl<D1, D2, T> Div<Q<D2, T>> for Q<D1, T>
+where
+    D1: Div<D2>,
+    T: Div<Output = T>,
+{
+    type Output = Q<<D1 as Div<D2>>::Output, T>;
+
+    fn div(self, other: Q<D2, T>) -> Self::Output {
+        Q {
+            magnitude: self.magnitude / other.magnitude,
+            dimension: PhantomData,
+        }
+    }
+}
+
+// Multiplication by scalar a (f64)
+impl<D, T> Mul<f64> for Q<D, T>
+where
+    T: MathOpsF64,
+{
+    type Output = Self;
+
+    fn mul(self, scalar: f64) -> Self::Output {
+        Self {
+            magnitude: self.magnitude * scalar,
+            dimension: PhantomData,
+        }
+    }
+}
+
+// Division by a scalar (f64)
+impl<D, T> Div<f64> for Q<D, T>
+where
+    T: MathOpsF64,
+{
+    type Output = Self;
+
+    fn div(self, scalar: f64) -> Self::Output {
+        Self {
+            magnitude: self.magnitude / scalar,
+            dimension: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fundamentals::base::*;
+    use crate::u;
+
+    #[test]
+    fn test_dimensions() {
+        assert_eq!(dim::Length::DIMENSION, LENGTH);
+        assert_eq!(dim::Dimensionless::DIMENSION, COUNT);
+        assert_eq!(dim::Force::DIMENSION, MASS * LENGTH / TIME.pow(2));
+        assert_eq!(
+            <dim::Energy as Div<dim::Time>>::Output::DIMENSION,
+            dim::Power::DIMENSION
+        );
+    }
+
+    #[test]
+    fn test_typed_arithmetic() -> Result<(), Error> {
+        let mass = Mass::new(2.0, &u!("kilogram")?)?;
+        let distance = Length::new(100.0, &u!("centimeter")?)?;
+        let time = Time::new(1.0, &u!("second")?)?;
+
+        let force: Force = mass * distance / (time * time);
+        assert_eq!(force.m_as(&u!("newton")?)?, 2.0);
+
+        let energy: Energy = force * (distance + distance * 2.0 - distance);
+        assert_eq!(energy.m_as(&u!("joule")?)?, 4.0);
+
+        let frequency: Frequency = Dimensionless::from_base(1.0) / time;
+        assert_eq!(frequency.dimensionality(), COUNT / TIME);
+
+        assert!(Length::new(1.0, &u!("second")?).is_err());
+        assert!(distance.m_as(&u!("gram")?).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dynamic_conversions() -> Result<(), Error> {
+        let celsius = Quantity::new(25.0, u!("degree_celsius")?);
+        let temperature = Temperature::try_from(celsius.clone())?;
+        assert_eq!(temperature.base_magnitude(), celsius.base_magnitude());
+
+        let dynamic: Quantity<f64> = temperature.into();
+        assert_eq!(dynamic.base_magnitude(), celsius.base_magnitude());
+        assert_eq!(dynamic.unit().name(), "degree_kelvin");
+        let speed = Length::new(10.0, &u!("meter")?)? / Time::new(2.0, &u!("second")?)?;
+        assert_eq!(format!("{}", Quantity::from(speed)), "5 m/s");
+        assert_eq!(dynamic.m_as(&u!("degree_celsius")?)?, 25.0);
+
+        assert_eq!(
+            Length::try_from(celsius).err(),
+            Some(Error::UnitsConversionError {
+                expected: LENGTH,
+                got: TEMPERATURE
+            })
+        );
+
+        Ok(())
+    }
+}
@@ -1,22 +1,46 @@
+use crate::errors::ArshinError as Error;
+use crate::formatting::{FormatSpec, format_unit};
 use crate::fundamentals::Dimension;
-use crate::transformations::{LinearTransformation, MathOpsF64, UnitTransformation};
+use crate::fundamentals::base::COUNT;
+use crate::prefixes::Prefix;
+use crate::registry::UnitRegistry;
+use crate::transformations::{
+    LinearTransformation, MathOpsF64, UnitConversion, UnitTransformation,
+};
+use std::borrow::Cow;
 use std::fmt;
 use std::ops::{Div, Mul};
+use std::sync::Arc;
 
 /// Represents a measurement unit with name, dimension, and transformation.
 ///
-/// Units can be base (identity), linear (e.g., km = 1000 * m), or decibel.
+/// Units can be base (identity), linear (e.g., km = 1000 * m), or logarithmic (e.g. decibel).
 /// Supports multiplication/division for derived units (e.g., m/s).
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Unit {
-    pub name: String,
-    pub dimensionality: Dimension,
-    pub transformation: UnitTransformation,
+    name: Cow<'static, str>,
+    symbol: Option<Cow<'static, str>>,
+    dimensionality: Dimension,
+    transformation: UnitTransformation,
+    /// Named units the unit is a product of, with their powers, for units built by
+    /// [`Unit::checked_mul`], [`Unit::checked_div`] and [`Unit::checked_pow`]; empty otherwise.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "<[_]>::is_empty")
+    )]
+    factors: Cow<'static, [(Unit, i64)]>,
 }
 
-impl<'a> fmt::Display for Unit {
+/// Writes the unit with symbols (`m/s^2`, see [`Unit::format`]); the alternate flag (`{:#}`)
+/// writes its name and dimensionality instead (`meter [length]`).
+impl fmt::Display for Unit {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{} [{}]", self.name, self.dimensionality)
+        if f.alternate() {
+            f.pad(&format!("{} [{}]", self.name, self.dimensionality))
+        } else {
+            f.pad(&self.format(&FormatSpec::default()))
+        }
     }
 }
 
@@ -36,12 +60,62 @@ impl Unit {
         transformation: UnitTransformation,
     ) -> Self {
         Self {
-            name: name.into(),
+            name: Cow::Owned(name.into()),
+            symbol: None,
             dimensionality: dimension,
             transformation,
+            factors: Cow::Borrowed(&[]),
         }
     }
 
+    /// Creates a unit borrowing its name and symbol, usable in `const` and `static` items
+    /// (e.g. the generated tables of [`StaticRegistry`](crate::registry::StaticRegistry)).
+    pub const fn new_static(
+        name: &'static str,
+        symbol: Option<&'static str>,
+        dimension: Dimension,
+        transformation: UnitTransformation,
+    ) -> Self {
+        Self::new_static_derived(name, symbol, dimension, transformation, &[])
+    }
+
+    /// Creates a unit built from `factors` (see [`Unit::factors`]) like [`Unit::new_static`], e.g.
+    /// for units of expressions resolved at compile time.
+    pub const fn new_static_derived(
+        name: &'static str,
+        symbol: Option<&'static str>,
+        dimension: Dimension,
+        transformation: UnitTransformation,
+        factors: &'static [(Unit, i64)],
+    ) -> Self {
+        Self {
+            name: Cow::Borrowed(name),
+            symbol: match symbol {
+                Some(symbol) => Some(Cow::Borrowed(symbol)),
+                None => None,
+            },
+            dimensionality: dimension,
+            transformation,
+            factors: Cow::Borrowed(factors),
+        }
+    }
+
+    /// Sets the short symbol of the unit (e.g. `m` for meter).
+    pub fn with_symbol(mut self, symbol: impl Into<String>) -> Self {
+        self.symbol = Some(Cow::Owned(symbol.into()));
+        self
+    }
+
+    pub(crate) fn without_symbol(mut self) -> Self {
+        self.symbol = None;
+        self
+    }
+
+    pub(crate) fn with_dimensionality(mut self, dimension: Dimension) -> Self {
+        self.dimensionality = dimension;
+        self
+    }
+
     /// Creates a base unit with identity transformation.
     ///
     /// # Examples
@@ -68,6 +142,79 @@ impl Unit {
         )
     }
 
+    /// Creates a unit with a user-defined nonlinear conversion (e.g. wire gauge or shoe size).
+    ///
+    /// Such units convert to and from other units of the same dimension, but do not accept
+    /// prefixes and cannot be multiplied, divided or raised to a power.
+    pub fn new_custom(
+        name: impl Into<String>,
+        dimension: Dimension,
+        conversion: impl UnitConversion + 'static,
+    ) -> Self {
+        Self::new(
+            name.into(),
+            dimension,
+            UnitTransformation::Custom(Arc::new(conversion)),
+        )
+    }
+
+    /// Creates a prefixed version of the unit (e.g. `kilo` + `meter`).
+    ///
+    /// The symbol, if any, gets the prefix symbol (e.g. `k` + `m`); prefixes without a symbol
+    /// produce a unit without symbol.
+    pub fn with_prefix(&self, prefix: &Prefix) -> Self {
+        let scale = match self.transformation {
+            UnitTransformation::Linear(LinearTransformation { scale, .. }) => scale,
+            _ => 1.0,
+        };
+
+        let unit = Self::new_linear(
+            format!("{}{}", prefix.name, self.name),
+            self.dimensionality,
+            scale * prefix.factor,
+            0.0,
+        );
+        match &self.symbol {
+            Some(symbol) if !prefix.symbol.is_empty() => {
+                unit.with_symbol(format!("{}{}", prefix.symbol, symbol))
+            }
+            _ => unit,
+        }
+    }
+
+    /// Checks if the unit has an offset (e.g. `degree_celsius`), i.e. measures absolute values
+    /// rather than differences.
+    pub fn is_biased(&self) -> bool {
+        matches!(self.transformation, UnitTransformation::Linear(LinearTransformation { offset, .. }) if offset != 0.0)
+    }
+
+    /// Creates the delta (difference) unit of a biased unit: the same scale without offset,
+    /// named `delta_<name>` with symbol `Δ<symbol>` (e.g. `delta_degree_celsius`, `Δ°C`).
+    ///
+    /// # Returns
+    /// `None` if the unit is not biased.
+    pub fn delta(&self) -> Option<Self> {
+        let UnitTransformation::Linear(LinearTransformation { scale, offset }) =
+            self.transformation
+        else {
+            return None;
+        };
+        if offset == 0.0 {
+            return None;
+        }
+
+        let unit = Self::new_linear(
+            format!("delta_{}", self.name),
+            self.dimensionality,
+            scale,
+            0.0,
+        );
+        Some(match &self.symbol {
+            Some(symbol) => unit.with_symbol(format!("Δ{}", symbol)),
+            None => unit,
+        })
+    }
+
     /// Converts a value in this unit to base units.
     pub fn to_base<T: MathOpsF64>(&self, value: T) -> T {
         self.transformation.to_base(value)
@@ -82,6 +229,10 @@ impl Unit {
         &self.name
     }
 
+    pub fn symbol(&self) -> Option<&str> {
+        self.symbol.as_deref()
+    }
+
     pub fn dimensionality(&self) -> &Dimension {
         &self.dimensionality
     }
@@ -90,6 +241,19 @@ impl Unit {
         &self.transformation
     }
 
+    /// Named units the unit is built from, with their powers, in the order of the operations
+    /// (e.g. `meter`, 1, `second`, 1, `meter`, -1 for `((meter * second) / meter)`); empty for
+    /// units that are not products, quotients or powers of other units.
+    pub fn factors(&self) -> &[(Unit, i64)] {
+        &self.factors
+    }
+
+    /// Formats the unit with a style and symbols or long names (e.g. `m/s^2`, `m·s⁻²`,
+    /// `\mathrm{m\,s^{-2}}`); `compact` has no effect on units.
+    pub fn format(&self, spec: &FormatSpec) -> String {
+        format_unit(self, spec)
+    }
+
     /// Checks if two units have the same dimensionality (compatible for conversion).
     ///
     /// # Returns
@@ -98,142 +262,258 @@ impl Unit {
         self.dimensionality == other.dimensionality
     }
 
+    /// Raises the unit to a power.
+    ///
+    /// # Panics
+    /// On biased or logarithmic units and zero power; see [`Unit::checked_pow`].
     pub fn pow(&self, power: i64) -> Self {
+        self.checked_pow(power)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Raises the unit to a power.
+    ///
+    /// # Errors
+    /// `BiasedUnitOperation`, `LogarithmicUnitOperation` or `CustomUnitOperation` if the unit has
+    /// an offset, logarithmic or custom transformation, `ZeroPower` if `power` is zero.
+    pub fn checked_pow(&self, power: i64) -> Result<Self, Error> {
+        let scale = self.multiplicative_scale("power")?;
+        if power == 0 {
+            return Err(Error::ZeroPower {
+                name: self.name.to_string(),
+            });
+        }
+
+        let name = format!("[{}]^{}", self.name, power);
+        let dimension = self.dimensionality().pow(power);
+        let unit = match self.transformation {
+            UnitTransformation::Identity => Unit::new_base(name, dimension),
+            _ => Self::new_linear(name, dimension, scale.powf(power as f64), 0.0),
+        };
+        let factors = self.terms().into_iter();
+        Ok(unit.with_factors(
+            factors
+                .map(|(unit, exponent)| (unit, exponent * power))
+                .collect(),
+        ))
+    }
+
+    /// Multiplies two units (e.g. `newton * meter`).
+    ///
+    /// # Errors
+    /// `BiasedUnitOperation`, `LogarithmicUnitOperation` or `CustomUnitOperation` if either unit
+    /// has an offset, logarithmic or custom transformation.
+    pub fn checked_mul(&self, other: &Unit) -> Result<Self, Error> {
+        let scale = self.multiplicative_scale("multiplication")?;
+        let other_scale = other.multiplicative_scale("multiplication")?;
+
+        let unit = Unit::new_linear(
+            format!("({} * {})", self.name, other.name),
+            self.dimensionality * other.dimensionality,
+            scale * other_scale,
+            0.0,
+        );
+        let mut factors = self.terms();
+        factors.extend(other.terms());
+        Ok(unit.with_factors(factors))
+    }
+
+    /// Divides two units (e.g. `meter / second`).
+    ///
+    /// # Errors
+    /// `BiasedUnitOperation`, `LogarithmicUnitOperation` or `CustomUnitOperation` if either unit
+    /// has an offset, logarithmic or custom transformation.
+    pub fn checked_div(&self, other: &Unit) -> Result<Self, Error> {
+        let scale = self.multiplicative_scale("division")?;
+        let other_scale = other.multiplicative_scale("division")?;
+
+        let unit = Unit::new_linear(
+            format!("({} / {})", self.name, other.name),
+            self.dimensionality / other.dimensionality,
+            scale / other_scale,
+            0.0,
+        );
+        let mut factors = self.terms();
+        factors.extend(
+            other
+                .terms()
+                .into_iter()
+                .map(|(unit, power)| (unit, -power)),
+        );
+        Ok(unit.with_factors(factors))
+    }
+
+    /// Scale of a multiplicative unit (identity or linear without offset), e.g. 1000 for kilometer.
+    ///
+    /// # Returns
+    /// `None` for biased, logarithmic and custom units.
+    pub fn scale(&self) -> Option<f64> {
         match self.transformation {
-            UnitTransformation::Decibel(_) => panic!("Cannot raise a decibel unit to a power"),
-            UnitTransformation::Linear(LinearTransformation { scale, offset }) => {
-                if offset != 0.0 {
-                    panic!("Cannot raise a biased unit to a power");
-                }
-
-                if power == 0 {
-                    panic!("Cannot raise a unit to zero power");
-                }
-
-                Self::new_linear(
-                    format!("[{}]^{}", self.name, power),
-                    self.dimensionality().pow(power),
-                    scale.powf(power as f64),
-                    0.0,
-                )
-            }
-            UnitTransformation::Identity => Unit::new_base(
-                format!("[{}]^{}", self.name, power),
-                self.dimensionality().pow(power),
-            ),
+            UnitTransformation::Identity => Some(1.0),
+            UnitTransformation::Linear(LinearTransformation { scale, offset: 0.0 }) => Some(scale),
+            _ => None,
         }
     }
-}
 
-impl Mul<Unit> for Unit {
-    type Output = Unit;
+    /// Reduces a derived unit: cancels repeated factors and merges their powers
+    /// (e.g. `((meter * second) / meter)` → `second`, `(meter * meter)` → `[meter]^2`).
+    ///
+    /// The scale and dimensionality are unchanged; a unit whose factors all cancel is named `1`,
+    /// and one reduced to a single named unit is that unit. Units that are not products or powers
+    /// of other units are returned as is.
+    pub fn reduced(&self) -> Self {
+        if !self.is_derived() {
+            return self.clone();
+        }
 
-    fn mul(self, rhs: Unit) -> Self::Output {
-        use UnitTransformation::*;
-
-        // Check for biased and non-linear transformations
-        match (self.transformation, rhs.transformation) {
-            (Linear(t1), _) if t1.offset != 0.0 => {
-                panic!(
-                    "Multiplication not permitted for unit '{}' with biased transformation",
-                    self.name
-                )
-            }
-            (_, Linear(t2)) if t2.offset != 0.0 => {
-                panic!(
-                    "Multiplication not permitted for unit '{}' with biased transformation",
-                    rhs.name
-                )
-            }
-            (Decibel(_), _) | (_, Decibel(_)) => {
-                panic!("Multiplication not supported for decibel transformations")
+        let factors = self.reduced_factors();
+        if let [(unit, 1)] = factors.as_slice() {
+            return (*unit).clone();
+        }
+
+        let name = factors_name(&factors);
+        let unit = match self.transformation {
+            UnitTransformation::Identity => Self::new_base(name, self.dimensionality),
+            _ => Self::new_linear(name, self.dimensionality, self.scale().unwrap_or(1.0), 0.0),
+        };
+        unit.with_factors(factors)
+    }
+
+    /// Reduces a derived unit (see [`Unit::reduced`]) and replaces it with the named unit of the
+    /// registry with the same dimensionality and scale, if any (e.g. `kilogram * meter / second^2`
+    /// → `newton`, `kilonewton * kilometer` → `Megajoule`).
+    ///
+    /// Unprefixed units are preferred to prefixed ones; dimensionless units are only reduced.
+    pub fn simplify(&self, registry: &UnitRegistry) -> Self {
+        let reduced = self.reduced();
+        if !self.is_derived() || reduced.dimensionality == COUNT {
+            return reduced;
+        }
+
+        match reduced.scale() {
+            Some(scale) => registry
+                .find_unit(reduced.dimensionality, scale)
+                .unwrap_or(reduced),
+            None => reduced,
+        }
+    }
+
+    /// Named units the unit is a product of, with their powers, repeated factors merged and
+    /// cancelled ones dropped (e.g. `kilogram`, `meter` and `second`, -2 for a newton built from
+    /// them); a named unit is its own single factor.
+    pub(crate) fn reduced_factors(&self) -> Vec<(&Unit, i64)> {
+        let mut factors: Vec<(&Unit, i64)> = Vec::new();
+        for (unit, power) in self.terms() {
+            match factors
+                .iter_mut()
+                .find(|(factor, _)| factor.name == unit.name)
+            {
+                Some((_, total)) => *total += power,
+                None => factors.push((unit, power)),
             }
-            _ => {}
         }
+        factors.retain(|(_, power)| *power != 0);
+        factors
+    }
 
-        // Combine names and dimensionalities
-        let new_name = format!("({} * {})", self.name, rhs.name);
-        let new_dimension = self.dimensionality * rhs.dimensionality;
+    /// Factors of the unit as built, or the unit itself if it is not built from others.
+    fn terms(&self) -> Vec<(&Unit, i64)> {
+        if self.factors.is_empty() {
+            vec![(self, 1)]
+        } else {
+            self.factors
+                .iter()
+                .map(|(unit, power)| (unit, *power))
+                .collect()
+        }
+    }
 
-        // Combine transformations
-        let scale = match self.transformation {
-            Identity => 1.0,
-            Linear(LinearTransformation { scale, .. }) => scale,
-            _ => unreachable!(),
-        };
-        let rhs_scale = match rhs.transformation {
-            Identity => 1.0,
-            Linear(LinearTransformation { scale, .. }) => scale,
-            _ => unreachable!(),
-        };
+    fn with_factors(mut self, factors: Vec<(&Unit, i64)>) -> Self {
+        self.factors = factors
+            .into_iter()
+            .map(|(unit, power)| (unit.clone(), power))
+            .collect::<Vec<_>>()
+            .into();
+        self
+    }
 
-        Unit::new(
-            new_name,
-            new_dimension,
-            Linear(LinearTransformation {
-                scale: scale * rhs_scale,
-                offset: 0.0,
+    /// Checks if the unit is a product, quotient or power of other units.
+    fn is_derived(&self) -> bool {
+        !self.factors.is_empty()
+    }
+
+    /// Scale of the unit for multiplicative operations, failing on biased, logarithmic and custom
+    /// units.
+    fn multiplicative_scale(&self, operation: &str) -> Result<f64, Error> {
+        match self.transformation {
+            UnitTransformation::Identity => Ok(1.0),
+            UnitTransformation::Linear(LinearTransformation { scale, offset: 0.0 }) => Ok(scale),
+            UnitTransformation::Linear(_) => Err(Error::BiasedUnitOperation {
+                name: self.name.to_string(),
+                operation: operation.into(),
             }),
-        )
+            UnitTransformation::Logarithmic(_) => Err(Error::LogarithmicUnitOperation {
+                name: self.name.to_string(),
+                operation: operation.into(),
+            }),
+            UnitTransformation::Custom(_) => Err(Error::CustomUnitOperation {
+                name: self.name.to_string(),
+                operation: operation.into(),
+            }),
+        }
     }
 }
 
-impl Div<Unit> for Unit {
+/// Panics on biased or logarithmic units; see [`Unit::checked_mul`].
+impl Mul<Unit> for Unit {
     type Output = Unit;
 
-    fn div(self, rhs: Unit) -> Self::Output {
-        use UnitTransformation::*;
-
-        // Check for biased and non-linear transformations
-        match (self.transformation, rhs.transformation) {
-            (Linear(t1), _) if t1.offset != 0.0 => {
-                panic!(
-                    "Multiplication not permitted for unit '{}' with biased transformation",
-                    self.name
-                )
-            }
-            (_, Linear(t2)) if t2.offset != 0.0 => {
-                panic!(
-                    "Multiplication not permitted for unit '{}' with biased transformation",
-                    rhs.name
-                )
-            }
-            (Decibel(_), _) | (_, Decibel(_)) => {
-                panic!("Multiplication not supported for decibel transformations")
-            }
-            _ => {}
-        }
-
-        // Combine names and dimensionalities
-        let new_name = format!("({} / {})", self.name, rhs.name);
-        let new_dimension = self.dimensionality / rhs.dimensionality;
+    fn mul(self, rhs: Unit) -> Self::Output {
+        self.checked_mul(&rhs)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+}
 
-        // Combine transformations
-        let scale = match self.transformation {
-            Identity => 1.0,
-            Linear(LinearTransformation { scale, .. }) => scale,
-            _ => unreachable!(),
-        };
-        let rhs_scale = match rhs.transformation {
-            Identity => 1.0,
-            Linear(LinearTransformation { scale, .. }) => scale,
-            _ => unreachable!(),
-        };
+/// Panics on biased or logarithmic units; see [`Unit::checked_div`].
+impl Div<Unit> for Unit {
+    type Output = Unit;
 
-        Unit::new(
-            new_name,
-            new_dimension,
-            Linear(LinearTransformation {
-                scale: scale / rhs_scale,
-                offset: 0.0,
-            }),
-        )
+    fn div(self, rhs: Unit) -> Self::Output {
+        self.checked_div(&rhs)
+            .unwrap_or_else(|error| panic!("{}", error))
     }
 }
 
+/// Builds the name of a product of factors, in the format of [`Unit::checked_mul`] and
+/// [`Unit::checked_div`] (e.g. `((kilogram * meter) / [second]^2)`).
+fn factors_name(factors: &[(&Unit, i64)]) -> String {
+    let term = |name: &str, power: i64| match power {
+        1 => name.to_string(),
+        _ => format!("[{}]^{}", name, power),
+    };
+
+    let numerator = factors
+        .iter()
+        .filter(|(_, power)| *power > 0)
+        .map(|(unit, power)| term(unit.name(), *power))
+        .reduce(|product, factor| format!("({} * {})", product, factor));
+
+    let name =
+        factors
+            .iter()
+            .filter(|(_, power)| *power < 0)
+            .fold(numerator, |product, (unit, power)| match product {
+                Some(product) => Some(format!("({} / {})", product, term(unit.name(), -power))),
+                None => Some(term(unit.name(), *power)),
+            });
+
+    name.unwrap_or_else(|| "1".into())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::fundamentals::base::{LENGTH, MASS, TEMPERATURE, TIME};
+    use crate::transformations::LogarithmicTransformation;
 
     use super::*;
 
@@ -269,6 +549,16 @@ mod tests {
         assert_eq!(unit.from_base(273.15), 0.0); // 273.15 K = 0°C
     }
 
+    #[test]
+    fn test_unit_symbol() {
+        let meter = Unit::new_base("meter", LENGTH);
+        assert_eq!(meter.symbol(), None);
+
+        let meter = meter.with_symbol("m");
+        assert_eq!(meter.symbol(), Some("m"));
+        assert_eq!(meter.name(), "meter");
+    }
+
     #[test]
     fn test_unit_is_compatible_with() {
         let meter = Unit::new_base("meter", LENGTH);
@@ -291,15 +581,19 @@ mod tests {
 
     #[test]
     fn test_unit_display() {
-        let meter = Unit::new_base("meter", LENGTH);
-        assert_eq!(format!("{}", meter), "meter [length]");
+        let meter = Unit::new_base("meter", LENGTH).with_symbol("m");
+        assert_eq!(format!("{}", meter), "m");
+        assert_eq!(format!("{:#}", meter), "meter [length]");
 
         let second = Unit::new_base("second", TIME);
-        assert_eq!(format!("{}", second), "second [time]");
+        assert_eq!(format!("{}", second), "second");
+        assert_eq!(format!("{:#}", second), "second [time]");
+        assert_eq!(format!("{}", meter.clone() / second.pow(2)), "m/second^2");
+        assert_eq!(format!("[{:>4}]", meter), "[   m]");
 
         let joule = Unit::new_base("joule", MASS * LENGTH.pow(2) / TIME.pow(2));
         assert_eq!(
-            format!("{}", joule),
+            format!("{:#}", joule),
             "joule [mass * [length]^2 * [time]^-2]"
         );
     }
@@ -319,4 +613,102 @@ mod tests {
         assert_eq!(kilometer_minute.to_base(1.0), 6.0e4);
         assert_eq!(*kilometer_minute.dimensionality(), dim);
     }
+
+    #[test]
+    fn test_delta_unit() {
+        let celsius =
+            Unit::new_linear("degree_celsius", TEMPERATURE, 1.0, 273.15).with_symbol("°C");
+        assert!(celsius.is_biased());
+
+        let delta = celsius.delta().unwrap();
+        assert_eq!(delta.name(), "delta_degree_celsius");
+        assert_eq!(delta.symbol(), Some("Δ°C"));
+        assert_eq!(delta.dimensionality(), &TEMPERATURE);
+        assert!(!delta.is_biased());
+        assert_eq!(delta.to_base(10.0), 10.0);
+
+        let kelvin = Unit::new_base("kelvin", TEMPERATURE);
+        assert_eq!(kelvin.delta(), None);
+
+        let heat_capacity = Unit::new_base("joule", MASS * LENGTH.pow(2) / TIME.pow(2))
+            .checked_div(&delta)
+            .unwrap();
+        assert_eq!(
+            heat_capacity.dimensionality(),
+            &(MASS * LENGTH.pow(2) / TIME.pow(2) / TEMPERATURE)
+        );
+    }
+
+    #[test]
+    fn test_checked_operations() {
+        let kilometer = Unit::new_linear("kilometer", LENGTH, 1000.0, 0.0);
+        let celsius = Unit::new_linear("celsius", TEMPERATURE, 1.0, 273.15);
+        let decibel = Unit::new(
+            "decibel",
+            Dimension::new([0; 10]),
+            UnitTransformation::Logarithmic(LogarithmicTransformation::decibel(1.0)),
+        );
+
+        assert_eq!(kilometer.checked_pow(2).unwrap().to_base(1.0), 1.0e6);
+        assert_eq!(
+            kilometer.checked_mul(&celsius),
+            Err(Error::BiasedUnitOperation {
+                name: "celsius".into(),
+                operation: "multiplication".into(),
+            })
+        );
+        assert_eq!(
+            decibel.checked_div(&kilometer),
+            Err(Error::LogarithmicUnitOperation {
+                name: "decibel".into(),
+                operation: "division".into(),
+            })
+        );
+        assert_eq!(
+            celsius.checked_pow(2).unwrap_err().to_string(),
+            "Cannot apply power to unit celsius with biased transformation"
+        );
+        assert_eq!(
+            kilometer.checked_pow(0),
+            Err(Error::ZeroPower {
+                name: "kilometer".into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_reduced_units() {
+        let meter = Unit::new_base("meter", LENGTH);
+        let kilometer = Unit::new_linear("kilometer", LENGTH, 1000.0, 0.0);
+        let second = Unit::new_base("second", TIME);
+
+        let unit = (meter.clone() * second.clone()) / meter.clone();
+        assert_eq!(unit.name(), "((meter * second) / meter)");
+        assert_eq!(unit.reduced().name(), "second");
+        assert_eq!(unit.reduced().dimensionality(), &TIME);
+
+        let area = (meter.clone() * meter.clone()) / second.clone() * second.clone().pow(2);
+        assert_eq!(area.reduced().name(), "([meter]^2 * second)");
+
+        let unit = kilometer.clone() / second.clone() / (meter.clone() / second.pow(2)).pow(2);
+        let reduced = unit.reduced();
+        assert_eq!(reduced.name(), "((kilometer * [second]^3) / [meter]^2)");
+        assert_eq!(reduced.to_base(1.0), unit.to_base(1.0));
+
+        let ratio = kilometer.clone() / kilometer.clone();
+        assert_eq!(ratio.reduced().name(), "1");
+        assert_eq!(ratio.reduced().dimensionality(), &Dimension::new([0; 10]));
+        assert_eq!(kilometer.reduced(), kilometer);
+
+        // Factors are kept with the unit, names are never parsed
+        let odd = Unit::new_base("(meter / second)", LENGTH).with_symbol("o");
+        assert_eq!(odd.reduced(), odd);
+        let unit = (odd.clone() * second.clone()).pow(2) / second.clone();
+        assert_eq!(unit.reduced().name(), "([(meter / second)]^2 * second)");
+        assert_eq!(
+            (unit / odd.clone()).reduced(),
+            (odd * second.clone()).reduced()
+        );
+        assert_eq!(((meter.clone() * second.clone()) / second).reduced(), meter);
+    }
 }
@@ -0,0 +1,51 @@
+//! Uses the exported macros from outside the crate, without importing anything they expand to.
+
+use arshin::{q, u};
+
+struct Context {
+    registry: arshin::UnitRegistry,
+}
+
+impl Context {
+    fn units(&self) -> &arshin::UnitRegistry {
+        &self.registry
+    }
+
+    fn time_unit(&self) -> Result<arshin::Unit, arshin::ArshinError> {
+        u!(&self.registry, "hour")
+    }
+}
+
+#[test]
+fn test_default_registry_macros() -> Result<(), arshin::ArshinError> {
+    let meter = u!("meter")?;
+    assert_eq!(q!(2.0, "kilometer")?.m_as(&meter)?, 2000.0);
+
+    let name = String::from("foot");
+    assert_eq!(u!(name)?.name(), "foot");
+    assert_eq!(
+        u!("parsec_typo").err(),
+        Some(arshin::ArshinError::RegistryDoesNotContainUnit {
+            name: "parsec_typo".into()
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_registry_expressions() -> Result<(), arshin::ArshinError> {
+    let context = Context {
+        registry: arshin::UnitRegistry::default(),
+    };
+
+    assert_eq!(u!(context.units(), "km")?.name(), "kilometer");
+    assert_eq!(context.time_unit()?.to_base(1.0), 3600.0);
+
+    let unit_name = "meter".to_string();
+    let quantity = q!(context.units(), 5.0, unit_name)?;
+    assert_eq!(quantity.m_as(&u!(context.registry, "centimeter")?)?, 500.0);
+    assert!(q!(&context.registry, 1.0, "not_a_unit").is_err());
+
+    Ok(())
+}